@@ -1,3 +1,32 @@
+use std::path::Path;
+
 fn main() {
+    embed_locales();
     tauri_build::build()
 }
+
+// The backend shares the frontend's translation files. Every JSON file in
+// src/i18n/locales is embedded, so adding a language is adding one file.
+fn embed_locales() {
+    let dir = Path::new("../src/i18n/locales");
+    println!("cargo:rerun-if-changed={}", dir.display());
+
+    let mut files: Vec<_> = std::fs::read_dir(dir)
+        .expect("failed to read src/i18n/locales")
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+
+    let mut out = String::from("&[\n");
+    for path in files {
+        let language = path.file_stem().unwrap().to_string_lossy().into_owned();
+        let absolute = std::fs::canonicalize(&path).unwrap();
+        out.push_str(&format!("    ({:?}, include_str!({:?})),\n", language, absolute));
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+    out.push_str("]\n");
+
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    std::fs::write(Path::new(&out_dir).join("locales.rs"), out).unwrap();
+}
@@ -0,0 +1,263 @@
+// Command line arguments. Autostart launches with `--minimized`; scripts and
+// launchers can open a view or add a goal. On Windows and Linux trivyn://
+// links arrive here as arguments too.
+
+use std::path::PathBuf;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use crate::commands::goals::create_goal;
+use crate::deep_link::{self, DeepLink};
+use crate::models::GoalLevel;
+
+pub const USAGE: &str = "\
+Usage: trivyn [options] [trivyn://...]
+  --minimized              Start in the tray without the floating window
+  --show-popover           Open the popover
+  --reflect weekly|monthly Reflect on the week or month that just ended
+  --add TITLE              Add a goal to the current period
+  --level daily|weekly|monthly
+                           Level of the goal added with --add (default: daily)
+  --data-dir PATH          Keep trivyn.db, backups and reports in PATH
+Links:
+  trivyn://add?title=TITLE[&level=LEVEL]
+                           Fill a goal into quick add to confirm
+  trivyn://toggle/GOAL_ID  Show a goal and ask before checking it off
+  trivyn://reflect/LEVEL[/PERIOD]
+                           Reflect on a period (default: the one that just ended)
+  trivyn://history[?date=YYYY-MM-DD]
+                           Open the history calendar on a day";
+
+/// Options given on the command line
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LaunchArgs {
+    pub minimized: bool,
+    pub show_popover: bool,
+    /// Weekly or monthly
+    pub reflect: Option<GoalLevel>,
+    pub add: Option<QuickAdd>,
+    pub data_dir: Option<PathBuf>,
+    /// trivyn:// links, opened after the other options
+    pub links: Vec<DeepLink>,
+}
+
+/// Goal added by `--add TITLE [--level LEVEL]`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QuickAdd {
+    pub title: String,
+    pub level: GoalLevel,
+}
+
+impl LaunchArgs {
+    /// Parse arguments, without the program name. Values can follow the
+    /// flag as the next argument or after `=`.
+    pub fn parse<I, S>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut parsed = LaunchArgs::default();
+        let mut title: Option<String> = None;
+        let mut level: Option<GoalLevel> = None;
+        let mut args = args.into_iter().map(Into::into);
+
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+                _ => (arg, None),
+            };
+
+            match flag.as_str() {
+                "--minimized" | "--show-popover" => {
+                    if inline.is_some() {
+                        return Err(format!("{} takes no value", flag));
+                    }
+                    if flag == "--minimized" {
+                        parsed.minimized = true;
+                    } else {
+                        parsed.show_popover = true;
+                    }
+                }
+                "--reflect" => {
+                    let value = take_value(&flag, inline, &mut args)?;
+                    parsed.reflect = match value.as_str() {
+                        "weekly" => Some(GoalLevel::Weekly),
+                        "monthly" => Some(GoalLevel::Monthly),
+                        _ => return Err(format!("--reflect expects weekly or monthly, got \"{}\"", value)),
+                    };
+                }
+                "--add" => {
+                    let value = take_value(&flag, inline, &mut args)?;
+                    if value.trim().is_empty() {
+                        return Err("--add needs a title".to_string());
+                    }
+                    title = Some(value.trim().to_string());
+                }
+                "--level" => {
+                    let value = take_value(&flag, inline, &mut args)?;
+                    level = Some(GoalLevel::from_str(&value).ok_or_else(|| {
+                        format!("--level expects daily, weekly or monthly, got \"{}\"", value)
+                    })?);
+                }
+                "--data-dir" => {
+                    let value = take_value(&flag, inline, &mut args)?;
+                    parsed.data_dir = Some(PathBuf::from(value));
+                }
+                _ if flag.starts_with(&format!("{}://", deep_link::SCHEME)) => {
+                    parsed.links.push(DeepLink::parse(&flag)?);
+                }
+                _ => return Err(format!("Unknown argument: {}", flag)),
+            }
+        }
+
+        parsed.add = match (title, level) {
+            (Some(title), level) => Some(QuickAdd {
+                title,
+                level: level.unwrap_or(GoalLevel::Daily),
+            }),
+            (None, Some(_)) => return Err("--level is only used with --add".to_string()),
+            (None, None) => None,
+        };
+
+        Ok(parsed)
+    }
+
+    /// Arguments of this process
+    pub fn from_env() -> Self {
+        Self::from_argv(std::env::args())
+    }
+
+    /// Parse a full command line, program name first. Invalid arguments are
+    /// reported and ignored so that a broken shortcut never keeps the app
+    /// from starting or coming to the front.
+    pub fn from_argv<I, S>(argv: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::parse(argv.into_iter().skip(1)).unwrap_or_else(|e| {
+            eprintln!("[cli] {}\n{}", e, USAGE);
+            Self::default()
+        })
+    }
+}
+
+fn take_value(
+    flag: &str,
+    inline: Option<String>,
+    rest: &mut impl Iterator<Item = String>,
+) -> Result<String, String> {
+    inline
+        .or_else(|| rest.next())
+        .ok_or_else(|| format!("{} needs a value", flag))
+}
+
+/// Carry out the arguments in the running app. `data_dir` is used earlier,
+/// when the database is opened.
+pub fn apply(app: &AppHandle, args: &LaunchArgs) {
+    // 1. 目標の追加（通常の追加経路を通す。リンクと違い確認は挟まない）
+    if let Some(add) = &args.add {
+        let now = chrono::Local::now().timestamp_millis();
+        if let Err(e) = create_goal(app, add.title.clone(), add.level, now, None) {
+            eprintln!("[cli] Failed to add goal: {}", e);
+        }
+    }
+
+    // 2. フローティングウィンドウ（--minimized ならトレイのみ）
+    if !args.minimized {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.show();
+        }
+    }
+
+    // 3. ポップオーバー
+    if let Some(level) = args.reflect {
+        let link = DeepLink::Reflect { level, period_key: None };
+        if let Err(e) = deep_link::open(app, &link) {
+            eprintln!("[cli] Failed to open reflection: {}", e);
+        }
+    } else if args.show_popover {
+        if let Some(window) = app.get_webview_window("popover") {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+
+    // 4. trivyn:// リンク
+    for link in &args.links {
+        if let Err(e) = deep_link::open(app, link) {
+            eprintln!("[cli] Failed to open link: {}", e);
+        }
+    }
+}
+
+/// Arguments of a second launch, handed over by the single-instance guard
+/// instead of starting another process on the same database
+pub fn apply_forwarded(app: &AppHandle, argv: Vec<String>) {
+    let args = LaunchArgs::from_argv(argv);
+
+    // 起動中のインスタンスはデータディレクトリを切り替えられない
+    if let Some(dir) = &args.data_dir {
+        if crate::db::data_dir(app).ok().as_ref() != Some(dir) {
+            eprintln!("[cli] Trivyn is already running; ignoring --data-dir {}", dir.display());
+        }
+    }
+
+    apply(app, &args);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_flags() {
+        assert_eq!(LaunchArgs::parse(Vec::<String>::new()).unwrap(), LaunchArgs::default());
+
+        let args = LaunchArgs::parse(["--minimized", "--reflect", "weekly", "--data-dir=/tmp/trivyn"]).unwrap();
+        assert!(args.minimized);
+        assert!(!args.show_popover);
+        assert_eq!(args.reflect, Some(GoalLevel::Weekly));
+        assert_eq!(args.data_dir, Some(PathBuf::from("/tmp/trivyn")));
+        assert_eq!(args.add, None);
+
+        assert!(LaunchArgs::parse(["--show-popover"]).unwrap().show_popover);
+    }
+
+    #[test]
+    fn test_parse_add() {
+        let args = LaunchArgs::parse(["--add", " Write RFC ", "--level", "weekly"]).unwrap();
+        assert_eq!(args.add, Some(QuickAdd { title: "Write RFC".to_string(), level: GoalLevel::Weekly }));
+
+        // レベルの指定がなければ今日の目標
+        let args = LaunchArgs::parse(["--level=monthly", "--add=Run 50km"]).unwrap();
+        assert_eq!(args.add.unwrap().level, GoalLevel::Monthly);
+        let args = LaunchArgs::parse(["--add", "Call bank"]).unwrap();
+        assert_eq!(args.add.unwrap().level, GoalLevel::Daily);
+    }
+
+    #[test]
+    fn test_from_argv() {
+        // プログラム名は読み飛ばす
+        let args = LaunchArgs::from_argv(["/Applications/Trivyn.app/Contents/MacOS/Trivyn", "--show-popover"]);
+        assert!(args.show_popover);
+
+        // Windows / Linux ではリンクが引数として渡される
+        let args = LaunchArgs::from_argv(["C:\\Program Files\\Trivyn\\Trivyn.exe", "trivyn://toggle/abc"]);
+        assert_eq!(args.links, [DeepLink::Toggle { goal_id: "abc".to_string() }]);
+
+        // 不正な引数があっても通常どおり起動する
+        assert_eq!(LaunchArgs::from_argv(["trivyn", "--reflect", "yearly"]), LaunchArgs::default());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(LaunchArgs::parse(["--reflect", "daily"]).is_err());
+        assert!(LaunchArgs::parse(["--reflect"]).is_err());
+        assert!(LaunchArgs::parse(["--add", "  "]).is_err());
+        assert!(LaunchArgs::parse(["--add", "x", "--level", "yearly"]).is_err());
+        assert!(LaunchArgs::parse(["--level", "daily"]).is_err());
+        assert!(LaunchArgs::parse(["--minimized=yes"]).is_err());
+        assert!(LaunchArgs::parse(["--verbose"]).is_err());
+        assert!(LaunchArgs::parse(["trivyn://delete/abc"]).is_err());
+    }
+}
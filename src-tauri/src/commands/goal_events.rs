@@ -0,0 +1,35 @@
+use chrono::{DateTime, Local};
+use tauri::State;
+use crate::commands::goals::get_week_start_setting;
+use crate::commands::periods::period_range;
+use crate::db::Database;
+use crate::models::{GoalEvent, GoalLevel};
+
+/// Timeline of everything that happened to one goal
+#[tauri::command]
+pub async fn get_goal_timeline(
+    goal_id: String,
+    db: State<'_, Database>,
+) -> Result<Vec<GoalEvent>, String> {
+    db.get_goal_events(&goal_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Timeline of all goal changes for the period of `level` containing `date`
+#[tauri::command]
+pub async fn get_period_timeline(
+    level: String,
+    date: i64,
+    db: State<'_, Database>,
+) -> Result<Vec<GoalEvent>, String> {
+    let goal_level = GoalLevel::from_str(&level)
+        .ok_or_else(|| format!("Invalid level: {}", level))?;
+    let dt = DateTime::from_timestamp_millis(date)
+        .ok_or("Invalid date timestamp")?
+        .with_timezone(&Local);
+
+    let (start, end) = period_range(goal_level, &dt, get_week_start_setting(&db));
+
+    db.get_goal_events_in_range(&level, start.timestamp_millis(), end.timestamp_millis())
+        .map_err(|e| e.to_string())
+}
@@ -1,8 +1,72 @@
-use tauri::{State, AppHandle, Emitter, Manager};
+use tauri::{State, AppHandle, Emitter, Manager, Runtime};
 use crate::db::Database;
 use crate::models::{Goal, GoalLevel};
+use crate::undo::{Change, UndoAction, UndoStack};
 use chrono::{DateTime, Local};
 
+/// Maximum number of goals per level and period ("Three Wins")
+pub const MAX_GOALS_PER_PERIOD: usize = 3;
+
+/// Broadcast event to all windows explicitly
+pub fn broadcast_goals_updated<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(main_window) = app.get_webview_window("main") {
+        let _ = main_window.emit("goals-updated", ());
+    }
+    if let Some(popover_window) = app.get_webview_window("popover") {
+        let _ = popover_window.emit("goals-updated", ());
+    }
+}
+
+pub fn get_week_start_setting(db: &Database) -> i32 {
+    db.get_setting("week_start")
+        .ok()
+        .and_then(|v| v.parse::<i32>().ok())
+        .unwrap_or(2) // Default to Monday
+}
+
+/// Whether the period containing `at` holds fewer than the maximum number
+/// of (non-trashed) goals for `level`
+pub fn period_has_room(
+    db: &Database,
+    level: GoalLevel,
+    at: &DateTime<Local>,
+) -> Result<bool, String> {
+    let week_start = get_week_start_setting(db);
+
+    let existing_goals = db.get_goals(Some(level.as_str()))
+        .map_err(|e| e.to_string())?;
+
+    let period_goals = existing_goals.iter()
+        .filter(|goal| {
+            let goal_dt = DateTime::from_timestamp_millis(goal.period_start)
+                .map(|dt| dt.with_timezone(&Local))
+                .unwrap_or_else(|| *at);
+
+            match level {
+                GoalLevel::Daily => crate::commands::periods::is_same_day(&goal_dt, at),
+                GoalLevel::Weekly => crate::commands::periods::is_same_week(&goal_dt, at, week_start),
+                GoalLevel::Monthly => crate::commands::periods::is_same_month(&goal_dt, at),
+            }
+        })
+        .count();
+
+    Ok(period_goals < MAX_GOALS_PER_PERIOD)
+}
+
+/// Fail if the period containing `at` already holds the maximum number of
+/// (non-trashed) goals for `level`
+pub fn ensure_period_has_room(
+    db: &Database,
+    level: GoalLevel,
+    at: &DateTime<Local>,
+) -> Result<(), String> {
+    if !period_has_room(db, level, at)? {
+        return Err("Maximum 3 goals per level".to_string());
+    }
+
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn get_goals(
     level: Option<String>,
@@ -19,11 +83,25 @@ pub async fn add_goal(
     period_start: i64,
     parent_goal_id: Option<String>,
     app: AppHandle,
-    db: State<'_, Database>,
 ) -> Result<Goal, String> {
     let goal_level = GoalLevel::from_str(&level)
         .ok_or_else(|| "Invalid goal level".to_string())?;
 
+    create_goal(&app, title, goal_level, period_start, parent_goal_id)
+}
+
+/// Add a goal with parent validation, the per-period limit, undo history and
+/// a `goals-updated` broadcast. Shared by the command, launch arguments and
+/// quick add. The limit applies to the period containing `period_start`.
+pub fn create_goal<R: Runtime>(
+    app: &AppHandle<R>,
+    title: String,
+    goal_level: GoalLevel,
+    period_start: i64,
+    parent_goal_id: Option<String>,
+) -> Result<Goal, String> {
+    let db = app.state::<Database>();
+
     // Validate parent link: must reference an existing goal exactly one level up
     if let Some(ref parent_id) = parent_goal_id {
         let expected_parent_level = match goal_level {
@@ -41,47 +119,22 @@ pub async fn add_goal(
         }
     }
 
-    // Get week_start setting for period calculations
-    let week_start = db.get_setting("week_start")
-        .ok()
-        .and_then(|v| v.parse::<i32>().ok())
-        .unwrap_or(2); // Default to Monday
-
-    // Check if we already have 3 goals for this level in the current period
-    let existing_goals = db.get_goals(Some(&level))
-        .map_err(|e| e.to_string())?;
-
-    // Filter goals by current period
-    let now = Local::now();
-    let current_period_goals: Vec<&Goal> = existing_goals.iter()
-        .filter(|goal| {
-            let goal_dt = DateTime::from_timestamp_millis(goal.period_start)
-                .map(|dt| dt.with_timezone(&Local))
-                .unwrap_or_else(|| now);
-
-            match goal_level {
-                GoalLevel::Daily => crate::commands::periods::is_same_day(&goal_dt, &now),
-                GoalLevel::Weekly => crate::commands::periods::is_same_week(&goal_dt, &now, week_start),
-                GoalLevel::Monthly => crate::commands::periods::is_same_month(&goal_dt, &now),
-            }
-        })
-        .collect();
-
-    if current_period_goals.len() >= 3 {
-        return Err("Maximum 3 goals per level".to_string());
-    }
+    // Check if we already have 3 goals for this level in the target period
+    let at = DateTime::from_timestamp_millis(period_start)
+        .map(|dt| dt.with_timezone(&Local))
+        .unwrap_or_else(Local::now);
+    ensure_period_has_room(&db, goal_level, &at)?;
 
     let goal = Goal::new(title, goal_level, period_start, parent_goal_id);
     db.add_goal(&goal)
         .map_err(|e| e.to_string())?;
 
-    // Broadcast event to all windows explicitly
-    if let Some(main_window) = app.get_webview_window("main") {
-        let _ = main_window.emit("goals-updated", ());
-    }
-    if let Some(popover_window) = app.get_webview_window("popover") {
-        let _ = popover_window.emit("goals-updated", ());
-    }
+    app.state::<UndoStack>().record(UndoAction::AddGoal, vec![Change::Goal {
+        before: None,
+        after: Some(goal.clone()),
+    }]);
+
+    broadcast_goals_updated(app);
 
     Ok(goal)
 }
@@ -90,19 +143,25 @@ pub async fn add_goal(
 pub async fn toggle_goal_completion(
     goal_id: String,
     app: AppHandle,
-    db: State<'_, Database>,
 ) -> Result<Goal, String> {
-    let goal = db.toggle_goal_completion(&goal_id)
+    toggle_goal(&app, &goal_id)
+}
+
+/// Toggle completion with undo history and a `goals-updated` broadcast.
+/// Shared by the command and the tray menu.
+pub fn toggle_goal<R: Runtime>(app: &AppHandle<R>, goal_id: &str) -> Result<Goal, String> {
+    let db = app.state::<Database>();
+    let before = db.get_goal(goal_id)
+        .map_err(|e| e.to_string())?;
+    let goal = db.toggle_goal_completion(goal_id)
         .map_err(|e| e.to_string())?;
 
-    // Broadcast event to all windows explicitly
-    if let Some(main_window) = app.get_webview_window("main") {
-        let _ = main_window.emit("goals-updated", ());
-    }
+    app.state::<UndoStack>().record(UndoAction::ToggleGoal, vec![Change::Goal {
+        before,
+        after: Some(goal.clone()),
+    }]);
 
-    if let Some(popover_window) = app.get_webview_window("popover") {
-        let _ = popover_window.emit("goals-updated", ());
-    }
+    broadcast_goals_updated(app);
 
     Ok(goal)
 }
@@ -113,37 +172,132 @@ pub async fn update_goal(
     title: String,
     app: AppHandle,
     db: State<'_, Database>,
+    history: State<'_, UndoStack>,
 ) -> Result<(), String> {
+    let before = db.get_goal(&goal_id)
+        .map_err(|e| e.to_string())?;
     db.update_goal(&goal_id, &title)
         .map_err(|e| e.to_string())?;
+    let after = db.get_goal(&goal_id)
+        .map_err(|e| e.to_string())?;
 
-    // Broadcast event to all windows explicitly
-    if let Some(main_window) = app.get_webview_window("main") {
-        let _ = main_window.emit("goals-updated", ());
-    }
-    if let Some(popover_window) = app.get_webview_window("popover") {
-        let _ = popover_window.emit("goals-updated", ());
+    if before != after {
+        history.record(UndoAction::EditGoal, vec![Change::Goal { before, after }]);
     }
 
+    broadcast_goals_updated(&app);
+
     Ok(())
 }
 
+/// Move a goal to the trash
 #[tauri::command]
 pub async fn delete_goal(
     goal_id: String,
     app: AppHandle,
     db: State<'_, Database>,
+    history: State<'_, UndoStack>,
 ) -> Result<(), String> {
+    let before = db.get_goal(&goal_id)
+        .map_err(|e| e.to_string())?;
     db.delete_goal(&goal_id)
         .map_err(|e| e.to_string())?;
+    let after = db.get_goal(&goal_id)
+        .map_err(|e| e.to_string())?;
 
-    // Broadcast event to all windows explicitly
-    if let Some(main_window) = app.get_webview_window("main") {
-        let _ = main_window.emit("goals-updated", ());
+    if before != after {
+        history.record(UndoAction::DeleteGoal, vec![Change::Goal { before, after }]);
     }
-    if let Some(popover_window) = app.get_webview_window("popover") {
-        let _ = popover_window.emit("goals-updated", ());
+
+    broadcast_goals_updated(&app);
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_trashed_goals(
+    db: State<'_, Database>,
+) -> Result<Vec<Goal>, String> {
+    db.get_trashed_goals()
+        .map_err(|e| e.to_string())
+}
+
+/// Bring a goal back from the trash. Fails if its period is already full.
+#[tauri::command]
+pub async fn restore_goal(
+    goal_id: String,
+    app: AppHandle,
+    db: State<'_, Database>,
+    history: State<'_, UndoStack>,
+) -> Result<Goal, String> {
+    let goal = db.get_goal(&goal_id)
+        .map_err(|e| e.to_string())?
+        .filter(|g| g.deleted_at.is_some())
+        .ok_or_else(|| "Goal not found in trash".to_string())?;
+
+    let period_dt = DateTime::from_timestamp_millis(goal.period_start)
+        .ok_or("Invalid period_start timestamp")?
+        .with_timezone(&Local);
+    ensure_period_has_room(&db, goal.level, &period_dt)?;
+
+    db.restore_goal(&goal_id)
+        .map_err(|e| e.to_string())?;
+
+    // 親がゴミ箱に残っていればリンクは外れている
+    let restored = db.get_goal(&goal_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Goal not found".to_string())?;
+    history.record(UndoAction::RestoreGoal, vec![Change::Goal {
+        before: Some(goal),
+        after: Some(restored.clone()),
+    }]);
+
+    broadcast_goals_updated(&app);
+
+    Ok(restored)
+}
+
+/// Permanently delete a single goal from the trash. Its children lose
+/// their parent link, so the other windows are told to reload.
+#[tauri::command]
+pub async fn purge_goal(
+    goal_id: String,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<(), String> {
+    let purged = db.purge_goal(&goal_id)
+        .map_err(|e| e.to_string())?;
+    if !purged {
+        return Err("Goal not found in trash".to_string());
     }
 
+    broadcast_goals_updated(&app);
+
     Ok(())
 }
+
+/// Permanently delete everything in the trash
+#[tauri::command]
+pub async fn empty_trash(
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<usize, String> {
+    let purged = db.purge_trash_before(i64::MAX)
+        .map_err(|e| e.to_string())?;
+    if purged > 0 {
+        broadcast_goals_updated(&app);
+    }
+    Ok(purged)
+}
+
+/// Purge goals that have been in the trash longer than `trash_retention_days`
+pub fn purge_expired_trash(db: &Database) -> Result<usize, String> {
+    let retention_days = db.get_setting("trash_retention_days")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(30);
+
+    let cutoff = chrono::Utc::now().timestamp_millis() - retention_days * 24 * 60 * 60 * 1000;
+    db.purge_trash_before(cutoff)
+        .map_err(|e| e.to_string())
+}
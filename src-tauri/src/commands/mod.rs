@@ -3,3 +3,10 @@ pub mod settings;
 pub mod reflections;
 pub mod periods;
 pub mod window;
+pub mod search;
+pub mod undo;
+pub mod goal_events;
+pub mod stats;
+pub mod reviews;
+pub mod notifications;
+pub mod quick_add;
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+use chrono::{Duration, Local};
+use tauri::{AppHandle, State};
+use crate::models::{QuietWindow, ReminderStatus};
+use crate::notifications::{self, NotificationCenter, ReminderAction};
+
+/// Handle a press on a reminder notification. A click on the notification
+/// itself arrives as `reflect_now`.
+#[tauri::command]
+pub async fn handle_reminder_action(action: String, app: AppHandle) -> Result<(), String> {
+    let action = ReminderAction::from_str(&action)
+        .ok_or_else(|| format!("Invalid reminder action: {}", action))?;
+    notifications::handle_action(&app, action)
+}
+
+/// What currently holds reminders back
+#[tauri::command]
+pub async fn get_reminder_status(
+    notifications: State<'_, Arc<NotificationCenter>>,
+) -> Result<ReminderStatus, String> {
+    Ok(notifications.status(Local::now().timestamp_millis()))
+}
+
+/// Hold back reminders for `minutes` and show the last one again afterwards
+#[tauri::command]
+pub async fn snooze_reminders(
+    minutes: u32,
+    notifications: State<'_, Arc<NotificationCenter>>,
+) -> Result<ReminderStatus, String> {
+    let now = Local::now();
+    notifications.snooze((now + Duration::minutes(minutes as i64)).timestamp_millis())?;
+    Ok(notifications.status(now.timestamp_millis()))
+}
+
+/// No more reminders until the current week is over
+#[tauri::command]
+pub async fn skip_reminders_for_week(
+    notifications: State<'_, Arc<NotificationCenter>>,
+) -> Result<ReminderStatus, String> {
+    let now = Local::now().timestamp_millis();
+    notifications.skip_week(now)?;
+    Ok(notifications.status(now))
+}
+
+/// Do not disturb for `hours`. Reminders due meanwhile are shown afterwards.
+#[tauri::command]
+pub async fn pause_reminders(
+    hours: u32,
+    notifications: State<'_, Arc<NotificationCenter>>,
+) -> Result<ReminderStatus, String> {
+    let now = Local::now();
+    notifications.pause((now + Duration::hours(hours as i64)).timestamp_millis())?;
+    Ok(notifications.status(now.timestamp_millis()))
+}
+
+/// End a pause or snooze early
+#[tauri::command]
+pub async fn resume_reminders(
+    notifications: State<'_, Arc<NotificationCenter>>,
+) -> Result<ReminderStatus, String> {
+    let now = Local::now().timestamp_millis();
+    notifications.resume()?;
+    notifications.deliver_pending(now)?;
+    Ok(notifications.status(now))
+}
+
+#[tauri::command]
+pub async fn get_quiet_hours(
+    notifications: State<'_, Arc<NotificationCenter>>,
+) -> Result<Vec<QuietWindow>, String> {
+    Ok(notifications.get_quiet_hours())
+}
+
+/// Replace the quiet-hour windows
+#[tauri::command]
+pub async fn set_quiet_hours(
+    windows: Vec<QuietWindow>,
+    notifications: State<'_, Arc<NotificationCenter>>,
+) -> Result<Vec<QuietWindow>, String> {
+    notifications.set_quiet_hours(&windows)?;
+    Ok(notifications.get_quiet_hours())
+}
@@ -1,4 +1,5 @@
-use chrono::{DateTime, Datelike, Duration, Local, NaiveDate};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime};
+use crate::models::GoalLevel;
 
 /// Check if a goal is in the same period as the target date
 #[tauri::command]
@@ -87,17 +88,158 @@ pub fn get_month_start(dt: &DateTime<Local>) -> DateTime<Local> {
         .unwrap()
 }
 
-/// Get week key in format "2025-W01"
+/// Start of the period following the one that starts at `period_start`
+pub fn next_period_start(level: GoalLevel, period_start: &DateTime<Local>) -> DateTime<Local> {
+    let next_date = match level {
+        GoalLevel::Daily => period_start.date_naive() + Duration::days(1),
+        GoalLevel::Weekly => period_start.date_naive() + Duration::days(7),
+        GoalLevel::Monthly => period_start.date_naive()
+            .checked_add_months(chrono::Months::new(1))
+            .unwrap(),
+    };
+    local_midnight(next_date)
+}
+
+/// Half-open range `[start, end)` of the period of `level` containing `dt`
+pub fn period_range(
+    level: GoalLevel,
+    dt: &DateTime<Local>,
+    week_start: i32,
+) -> (DateTime<Local>, DateTime<Local>) {
+    let start = match level {
+        GoalLevel::Daily => get_day_start(dt),
+        GoalLevel::Weekly => get_week_start(dt, week_start),
+        GoalLevel::Monthly => get_month_start(dt),
+    };
+    let end = next_period_start(level, &start);
+    (start, end)
+}
+
+/// Earliest start of a new day, week or month after `dt`, with the levels
+/// whose new period starts at that instant
+pub fn next_rollover(dt: &DateTime<Local>, week_start: i32) -> (DateTime<Local>, Vec<GoalLevel>) {
+    let ends = [GoalLevel::Daily, GoalLevel::Weekly, GoalLevel::Monthly]
+        .map(|level| (level, period_range(level, dt, week_start).1));
+    let at = ends.iter().map(|(_, end)| *end).min().unwrap();
+    let levels = ends.iter().filter(|(_, end)| *end == at).map(|(level, _)| *level).collect();
+    (at, levels)
+}
+
+/// Midnight of `date` in local time. On days where midnight does not exist
+/// (DST starting at 00:00) the earliest valid instant of the day is used.
+pub fn local_midnight(date: NaiveDate) -> DateTime<Local> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+    midnight
+        .and_local_timezone(Local)
+        .earliest()
+        .unwrap_or_else(|| {
+            (midnight + Duration::hours(1))
+                .and_local_timezone(Local)
+                .earliest()
+                .unwrap()
+        })
+}
+
+/// Week key in format "2025-W01". Must match `getWeekNumber` in
+/// src/utils/periods.ts, which keys the reflections the UI saves: weeks are
+/// numbered within the year of their first day, counting the (Sunday based)
+/// week containing January 1st as week 1.
+pub fn week_key(dt: &DateTime<Local>, week_start: i32) -> String {
+    week_key_for_start(get_week_start(dt, week_start).date_naive())
+}
+
+fn week_key_for_start(start: NaiveDate) -> String {
+    let jan1 = NaiveDate::from_ymd_opt(start.year(), 1, 1).unwrap();
+    let week = (start.ordinal0() + jan1.weekday().num_days_from_sunday() + 7) / 7;
+    format!("{}-W{:02}", start.year(), week)
+}
+
+/// Rewrite a week key stored before keys followed `getWeekNumber`, when they
+/// held the ISO week of the week's first day
+pub fn week_key_from_iso(key: &str, week_start: i32) -> Option<String> {
+    let (year, week) = key.split_once("-W")?;
+    let (year, week) = (year.parse::<i32>().ok()?, week.parse::<u32>().ok()?);
+    let monday = NaiveDate::from_isoywd_opt(year, week, chrono::Weekday::Mon)?;
+    // 週の初日はこの ISO 週の中で設定の曜日にあたる日
+    let offset = ((week_start - 1) - 1).rem_euclid(7);
+    Some(week_key_for_start(monday + Duration::days(offset as i64)))
+}
+
+/// Month key in format "2025-01"
+pub fn month_key(dt: &DateTime<Local>) -> String {
+    format!("{}-{:02}", dt.year(), dt.month())
+}
+
+/// Day key in format "2025-01-31"
+pub fn day_key(dt: &DateTime<Local>) -> String {
+    format!("{}-{:02}-{:02}", dt.year(), dt.month(), dt.day())
+}
+
+/// First day of the period identified by `key`, the inverse of `period_key`
+pub fn period_key_start(level: GoalLevel, key: &str, week_start: i32) -> Option<NaiveDate> {
+    match level {
+        GoalLevel::Daily => NaiveDate::parse_from_str(key, "%Y-%m-%d").ok(),
+        GoalLevel::Monthly => NaiveDate::parse_from_str(&format!("{key}-01"), "%Y-%m-%d").ok(),
+        GoalLevel::Weekly => {
+            let (year, week) = key.split_once("-W")?;
+            let (year, week) = (year.parse::<i32>().ok()?, week.parse::<u32>().ok()?);
+            let jan1 = NaiveDate::from_ymd_opt(year, 1, 1)?;
+            let target_weekday = (week_start - 1).rem_euclid(7) as u32;
+
+            // Week `week` covers the seven ordinals below; exactly one of them
+            // falls on the configured first day of the week
+            let first = (7 * week as i64) - 7 - jan1.weekday().num_days_from_sunday() as i64;
+            (first..first + 7)
+                .filter(|&ordinal0| ordinal0 >= 0)
+                .filter_map(|ordinal0| jan1.checked_add_signed(Duration::days(ordinal0)))
+                .filter(|date| date.year() == year)
+                .find(|date| date.weekday().num_days_from_sunday() == target_weekday)
+        }
+    }
+}
+
+/// Key of the period of `level` that contains the same date one year earlier
+pub fn same_period_last_year(level: GoalLevel, key: &str, week_start: i32) -> Option<String> {
+    let start = period_key_start(level, key, week_start)?;
+    let last_year = start.checked_sub_months(chrono::Months::new(12))?;
+    period_key(level, local_midnight(last_year).timestamp_millis(), week_start)
+}
+
+/// Parse a local time of day setting in "HH:MM" format
+pub fn parse_time_of_day(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+/// Period key of the given level for a timestamp (milliseconds)
+pub fn period_key(level: GoalLevel, timestamp: i64, week_start: i32) -> Option<String> {
+    let dt = DateTime::from_timestamp_millis(timestamp)?.with_timezone(&Local);
+    Some(match level {
+        GoalLevel::Daily => day_key(&dt),
+        GoalLevel::Weekly => week_key(&dt, week_start),
+        GoalLevel::Monthly => month_key(&dt),
+    })
+}
+
+/// Get week key in format "2025-W01". Weeks are numbered like
+/// `getWeekNumber` in the frontend, not by ISO 8601: the week containing
+/// January 1st is week 1 of its year.
 #[tauri::command]
 pub fn get_week_key(date: i64, week_start: i32) -> Result<String, String> {
     let dt = DateTime::from_timestamp_millis(date)
         .ok_or("Invalid date timestamp")?
         .with_timezone(&Local);
 
-    let week_start_dt = get_week_start(&dt, week_start);
-    let iso_week = week_start_dt.iso_week();
+    Ok(week_key(&dt, week_start))
+}
+
+/// Get day key in format "2025-01-31"
+#[tauri::command]
+pub fn get_day_key(date: i64) -> Result<String, String> {
+    let dt = DateTime::from_timestamp_millis(date)
+        .ok_or("Invalid date timestamp")?
+        .with_timezone(&Local);
 
-    Ok(format!("{}-W{:02}", iso_week.year(), iso_week.week()))
+    Ok(day_key(&dt))
 }
 
 /// Get month key in format "2025-01"
@@ -107,7 +249,7 @@ pub fn get_month_key(date: i64) -> Result<String, String> {
         .ok_or("Invalid date timestamp")?
         .with_timezone(&Local);
 
-    Ok(format!("{}-{:02}", dt.year(), dt.month()))
+    Ok(month_key(&dt))
 }
 
 #[cfg(test)]
@@ -197,4 +339,92 @@ mod tests {
         assert_eq!(month_start.minute(), 0);
         assert_eq!(month_start.second(), 0);
     }
+
+    #[test]
+    fn test_parse_time_of_day() {
+        assert_eq!(parse_time_of_day("21:00"), NaiveTime::from_hms_opt(21, 0, 0));
+        assert_eq!(parse_time_of_day(" 07:30 "), NaiveTime::from_hms_opt(7, 30, 0));
+        assert_eq!(parse_time_of_day(""), None);
+        assert_eq!(parse_time_of_day("25:00"), None);
+    }
+
+    #[test]
+    fn test_week_key_matches_frontend_numbering() {
+        // 2027-01-01 is a Friday: the partial week containing it is week 1
+        let dt = Local.with_ymd_and_hms(2027, 1, 6, 12, 0, 0).unwrap();
+        assert_eq!(week_key(&dt, 2), "2027-W02");
+
+        // A week keeps the year of its first day
+        let dt = Local.with_ymd_and_hms(2026, 1, 2, 12, 0, 0).unwrap();
+        assert_eq!(week_key(&dt, 2), "2025-W53");
+
+        let dt = Local.with_ymd_and_hms(2025, 12, 3, 12, 0, 0).unwrap();
+        assert_eq!(week_key(&dt, 2), "2025-W49");
+    }
+
+    #[test]
+    fn test_week_key_from_iso() {
+        for week_start in 1..=7 {
+            let mut date = NaiveDate::from_ymd_opt(2024, 12, 1).unwrap();
+            while date.year() < 2027 {
+                let dt = local_midnight(date) + Duration::hours(12);
+                // 以前の保存形式: 週の初日の ISO 週
+                let iso = get_week_start(&dt, week_start).iso_week();
+                let old = format!("{}-W{:02}", iso.year(), iso.week());
+                assert_eq!(week_key_from_iso(&old, week_start), Some(week_key(&dt, week_start)), "{old} ws={week_start}");
+                date += Duration::days(1);
+            }
+        }
+        assert_eq!(week_key_from_iso("", 2), None);
+    }
+
+    #[test]
+    fn test_period_key_start_roundtrip() {
+        for week_start in 1..=7 {
+            let mut date = NaiveDate::from_ymd_opt(2024, 12, 1).unwrap();
+            while date.year() < 2027 {
+                let dt = local_midnight(date) + Duration::hours(12);
+                let key = week_key(&dt, week_start);
+                let start = period_key_start(GoalLevel::Weekly, &key, week_start).unwrap();
+                assert_eq!(start, get_week_start(&dt, week_start).date_naive(), "{key} ws={week_start}");
+                date += Duration::days(1);
+            }
+        }
+
+        assert_eq!(
+            period_key_start(GoalLevel::Monthly, "2025-07", 2),
+            NaiveDate::from_ymd_opt(2025, 7, 1)
+        );
+        assert_eq!(
+            period_key_start(GoalLevel::Daily, "2025-07-15", 2),
+            NaiveDate::from_ymd_opt(2025, 7, 15)
+        );
+        assert_eq!(period_key_start(GoalLevel::Weekly, "2025-07", 2), None);
+    }
+
+    #[test]
+    fn test_same_period_last_year() {
+        assert_eq!(same_period_last_year(GoalLevel::Daily, "2024-02-29", 2).as_deref(), Some("2023-02-28"));
+        assert_eq!(same_period_last_year(GoalLevel::Monthly, "2026-01", 2).as_deref(), Some("2025-01"));
+        // Week starting Mon 2025-12-01 -> the week containing 2024-12-01 (a Sunday)
+        assert_eq!(same_period_last_year(GoalLevel::Weekly, "2025-W49", 2).as_deref(), Some("2024-W48"));
+    }
+
+    #[test]
+    fn test_next_rollover() {
+        // Sunday 2025-11-30: Monday starts a new day, week and month
+        let sunday = Local.with_ymd_and_hms(2025, 11, 30, 23, 59, 59).unwrap();
+        let (at, levels) = next_rollover(&sunday, 2);
+        assert_eq!(at, Local.with_ymd_and_hms(2025, 12, 1, 0, 0, 0).unwrap());
+        assert_eq!(levels, [GoalLevel::Daily, GoalLevel::Weekly, GoalLevel::Monthly]);
+
+        // With Sunday-based weeks the week already started that day
+        let (_, levels) = next_rollover(&sunday, 1);
+        assert_eq!(levels, [GoalLevel::Daily, GoalLevel::Monthly]);
+
+        // Exactly at midnight the next rollover is the following one
+        let (at, levels) = next_rollover(&(sunday + Duration::seconds(1)), 2);
+        assert_eq!(at, Local.with_ymd_and_hms(2025, 12, 2, 0, 0, 0).unwrap());
+        assert_eq!(levels, [GoalLevel::Daily]);
+    }
 }
@@ -0,0 +1,35 @@
+use tauri::{AppHandle, State};
+use crate::cli::QuickAdd;
+use crate::models::{Goal, GoalLevel};
+use crate::quick_add::{self, PendingGoal, QuickAddPreview};
+
+/// What the quick-add line would add. Errors are for lines that cannot be
+/// read yet (no title, a past date); a full period is reported in the preview.
+#[tauri::command]
+pub async fn preview_quick_add(input: String, app: AppHandle) -> Result<QuickAddPreview, String> {
+    quick_add::preview(&app, &input)
+}
+
+#[tauri::command]
+pub async fn commit_quick_add(input: String, app: AppHandle) -> Result<Goal, String> {
+    quick_add::commit(&app, &input)
+}
+
+/// Preview of the goal a link filled in. Its title is not parsed, so it is
+/// added exactly as the link gave it.
+#[tauri::command]
+pub async fn preview_quick_add_goal(title: String, level: GoalLevel, app: AppHandle) -> Result<QuickAddPreview, String> {
+    quick_add::preview_goal(&app, &QuickAdd { title, level })
+}
+
+#[tauri::command]
+pub async fn commit_quick_add_goal(title: String, level: GoalLevel, app: AppHandle) -> Result<Goal, String> {
+    quick_add::commit_goal(&app, &QuickAdd { title, level })
+}
+
+/// Goal to start with, when a link asked to add one. Taken once by the
+/// window on load and on `quick-add-opened`.
+#[tauri::command]
+pub async fn take_quick_add_goal(pending: State<'_, PendingGoal>) -> Result<Option<QuickAdd>, String> {
+    Ok(pending.take())
+}
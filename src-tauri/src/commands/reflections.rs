@@ -1,6 +1,10 @@
-use tauri::State;
+use tauri::{AppHandle, State};
 use crate::db::Database;
-use crate::models::{Reflection, GoalLevel};
+use crate::models::{Reflection, ReflectionDetails, ReflectionPrompt, ReflectionRevision, GoalLevel};
+use crate::undo::{Change, UndoAction, UndoStack};
+use crate::commands::goals::broadcast_goals_updated;
+use crate::commands::goals::get_week_start_setting;
+use crate::commands::periods::same_period_last_year;
 
 #[tauri::command]
 pub async fn get_reflection(
@@ -12,27 +16,69 @@ pub async fn get_reflection(
         .map_err(|e| e.to_string())
 }
 
+/// Save the reflection of a period. When `details` is omitted the rating,
+/// notes and prompt answers already stored for the period are kept.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn save_reflection(
     level: String,
     period_key: String,
     insight_1: Option<String>,
     insight_2: Option<String>,
     insight_3: Option<String>,
+    details: Option<ReflectionDetails>,
+    app: AppHandle,
     db: State<'_, Database>,
+    history: State<'_, UndoStack>,
 ) -> Result<Reflection, String> {
     let level_enum = GoalLevel::from_str(&level)
         .ok_or_else(|| "Invalid level".to_string())?;
 
+    if let Some(ref details) = details {
+        details.validate()?;
+    }
+
+    let before = db.get_reflection(&level, &period_key)
+        .map_err(|e| e.to_string())?;
+
+    let details = details
+        .or_else(|| before.as_ref().map(|r| r.details.clone()))
+        .unwrap_or_default();
+
     let reflection = Reflection::new(
         level_enum,
-        period_key,
+        period_key.clone(),
         insight_1,
         insight_2,
         insight_3,
-    );
+    )
+    .with_details(details);
+
+    // Saving unchanged content would only bump updated_at and add an empty revision
+    if let Some(existing) = before.as_ref().filter(|r| r.has_same_content(&reflection)) {
+        return Ok(existing.clone());
+    }
+
+    let saved = db.save_reflection(&reflection)
+        .map_err(|e| e.to_string())?;
 
-    db.save_reflection(&reflection)
+    history.record(UndoAction::SaveReflection, vec![Change::Reflection {
+        before,
+        after: Some(saved.clone()),
+    }]);
+    broadcast_goals_updated(&app);
+
+    Ok(saved)
+}
+
+/// Earlier versions of a reflection, newest first
+#[tauri::command]
+pub async fn get_reflection_revisions(
+    level: String,
+    period_key: String,
+    db: State<'_, Database>,
+) -> Result<Vec<ReflectionRevision>, String> {
+    db.get_reflection_revisions(&level, &period_key)
         .map_err(|e| e.to_string())
 }
 
@@ -44,3 +90,110 @@ pub async fn get_reflections_by_level(
     db.get_reflections_by_level(&level)
         .map_err(|e| e.to_string())
 }
+
+/// Daily reflections for the days from `from` to `to` (both inclusive),
+/// oldest first
+#[tauri::command]
+pub async fn get_daily_reflections(
+    from: i64,
+    to: i64,
+    db: State<'_, Database>,
+) -> Result<Vec<Reflection>, String> {
+    db.get_reflections_in_period_range(GoalLevel::Daily, from, to, 0)
+        .map_err(|e| e.to_string())
+}
+
+/// Reflections of `level` for every period overlapping `[from, to]`,
+/// oldest first (e.g. all weeks of a quarter)
+#[tauri::command]
+pub async fn get_reflections_in_range(
+    level: String,
+    from: i64,
+    to: i64,
+    db: State<'_, Database>,
+) -> Result<Vec<Reflection>, String> {
+    let level_enum = GoalLevel::from_str(&level)
+        .ok_or_else(|| "Invalid level".to_string())?;
+
+    db.get_reflections_in_period_range(level_enum, from, to, get_week_start_setting(&db))
+        .map_err(|e| e.to_string())
+}
+
+/// Reflection of the same period one year before `period_key`
+#[tauri::command]
+pub async fn get_reflection_last_year(
+    level: String,
+    period_key: String,
+    db: State<'_, Database>,
+) -> Result<Option<Reflection>, String> {
+    let level_enum = GoalLevel::from_str(&level)
+        .ok_or_else(|| "Invalid level".to_string())?;
+    let key = same_period_last_year(level_enum, &period_key, get_week_start_setting(&db))
+        .ok_or_else(|| format!("Invalid period key: {}", period_key))?;
+
+    db.get_reflection(&level, &key)
+        .map_err(|e| e.to_string())
+}
+
+/// Reflections of all levels for `[from, to]`, ordered by period
+#[tauri::command]
+pub async fn get_reflection_timeline(
+    from: i64,
+    to: i64,
+    db: State<'_, Database>,
+) -> Result<Vec<Reflection>, String> {
+    db.get_reflection_timeline(from, to, get_week_start_setting(&db))
+        .map_err(|e| e.to_string())
+}
+
+/// Custom reflection prompts of a level
+#[tauri::command]
+pub async fn get_reflection_prompts(
+    level: String,
+    include_archived: Option<bool>,
+    db: State<'_, Database>,
+) -> Result<Vec<ReflectionPrompt>, String> {
+    GoalLevel::from_str(&level)
+        .ok_or_else(|| "Invalid level".to_string())?;
+
+    db.get_reflection_prompts(&level, include_archived.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReflectionPromptInput {
+    /// Id of an existing prompt, or None for a new one
+    pub id: Option<String>,
+    pub text: String,
+}
+
+/// Replace the prompt set of a level, in the given order
+#[tauri::command]
+pub async fn set_reflection_prompts(
+    level: String,
+    prompts: Vec<ReflectionPromptInput>,
+    db: State<'_, Database>,
+) -> Result<Vec<ReflectionPrompt>, String> {
+    let level_enum = GoalLevel::from_str(&level)
+        .ok_or_else(|| "Invalid level".to_string())?;
+
+    let prompts: Vec<ReflectionPrompt> = prompts
+        .into_iter()
+        .filter(|p| !p.text.trim().is_empty())
+        .enumerate()
+        .map(|(position, p)| ReflectionPrompt {
+            id: p.id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            level: level_enum,
+            text: p.text.trim().to_string(),
+            position: position as i32,
+            archived_at: None,
+        })
+        .collect();
+
+    db.set_reflection_prompts(&level, &prompts)
+        .map_err(|e| e.to_string())?;
+
+    db.get_reflection_prompts(&level, false)
+        .map_err(|e| e.to_string())
+}
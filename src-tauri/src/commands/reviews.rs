@@ -0,0 +1,44 @@
+use chrono::{DateTime, Local};
+use tauri::{AppHandle, State};
+use crate::commands::goals::get_week_start_setting;
+use crate::db::Database;
+use crate::i18n::Locale;
+use crate::models::{GoalLevel, ReviewReport};
+
+fn build_review_at(level: &str, date: i64, db: &Database) -> Result<ReviewReport, String> {
+    let goal_level = GoalLevel::from_str(level)
+        .ok_or_else(|| format!("Invalid level: {}", level))?;
+    let dt = DateTime::from_timestamp_millis(date)
+        .ok_or("Invalid date timestamp")?
+        .with_timezone(&Local);
+
+    crate::review::build_review(db, goal_level, &dt, &Local::now(), get_week_start_setting(db))
+        .map_err(|e| e.to_string())
+}
+
+/// Review report for the period of `level` containing `date`
+#[tauri::command]
+pub async fn get_review_report(
+    level: String,
+    date: i64,
+    db: State<'_, Database>,
+) -> Result<ReviewReport, String> {
+    build_review_at(&level, date, &db)
+}
+
+/// Write the review report for the period of `level` containing `date` to the
+/// report archive and return the path of the Markdown file
+#[tauri::command]
+pub async fn export_review_report(
+    level: String,
+    date: i64,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<String, String> {
+    let report = build_review_at(&level, date, &db)?;
+    let dir = crate::review::reports_dir(&app).map_err(|e| e.to_string())?;
+
+    crate::review::save_review(&report, &Locale::for_db(&db), &dir)
+        .map(|path| path.to_string_lossy().into_owned())
+        .map_err(|e| e.to_string())
+}
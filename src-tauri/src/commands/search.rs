@@ -0,0 +1,16 @@
+use tauri::State;
+use crate::db::Database;
+use crate::models::{SearchFilters, SearchHit};
+
+/// Full-text search over goals, notes and reflection insights
+#[tauri::command]
+pub async fn search(
+    query: String,
+    filters: Option<SearchFilters>,
+    db: State<'_, Database>,
+) -> Result<Vec<SearchHit>, String> {
+    let week_start = crate::commands::goals::get_week_start_setting(&db);
+
+    db.search(&query, &filters.unwrap_or_default(), week_start)
+        .map_err(|e| e.to_string())
+}
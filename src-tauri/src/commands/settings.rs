@@ -3,6 +3,8 @@ use tauri_plugin_autostart::ManagerExt;
 use std::collections::HashMap;
 use serde::Serialize;
 use crate::db::Database;
+use crate::hotkeys::{self, HotkeyAction, HotkeyBinding};
+use crate::i18n::Locale;
 use crate::tray;
 
 #[derive(Clone, Serialize)]
@@ -67,6 +69,13 @@ pub async fn get_all_settings(
         .map_err(|e| e.to_string())
 }
 
+/// Language "system" resolves to, so the windows match the tray and
+/// notifications
+#[tauri::command]
+pub async fn get_system_language() -> Result<String, String> {
+    Ok(Locale::from_setting("system").language().to_string())
+}
+
 #[tauri::command]
 pub async fn set_theme(
     theme: String,
@@ -85,6 +94,39 @@ pub async fn set_theme(
     Ok(())
 }
 
+/// Show this week's goals in the tray menu next to today's
+#[tauri::command]
+pub async fn set_tray_show_weekly_goals(
+    enabled: bool,
+    app: AppHandle,
+    db: State<'_, Database>,
+) -> Result<(), String> {
+    db.set_setting("tray_show_weekly_goals", if enabled { "true" } else { "false" })
+        .map_err(|e| e.to_string())?;
+
+    tray::refresh_tray_menu(&app)
+        .map_err(|e| e.to_string())
+}
+
+/// Global shortcuts with the reason any of them is inactive
+#[tauri::command]
+pub async fn get_hotkeys(app: AppHandle) -> Result<Vec<HotkeyBinding>, String> {
+    Ok(hotkeys::bindings(&app))
+}
+
+/// Rebind a global shortcut; an empty `shortcut` disables it. Fails on
+/// invalid accelerators and on shortcuts already in use.
+#[tauri::command]
+pub async fn set_hotkey(
+    action: String,
+    shortcut: String,
+    app: AppHandle,
+) -> Result<HotkeyBinding, String> {
+    let action = HotkeyAction::from_id(&action)
+        .ok_or_else(|| format!("Invalid hotkey action: {}", action))?;
+    hotkeys::rebind(&app, action, &shortcut)
+}
+
 #[tauri::command]
 pub async fn enable_autostart(
     app: AppHandle,
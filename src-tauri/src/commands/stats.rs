@@ -0,0 +1,35 @@
+use chrono::Local;
+use tauri::State;
+use crate::commands::goals::get_week_start_setting;
+use crate::db::Database;
+use crate::models::{AlignmentReport, GoalLevel, GoalStats};
+
+/// Completion statistics for goals whose period starts in `[from, to)`
+#[tauri::command]
+pub async fn get_stats(
+    from: i64,
+    to: i64,
+    db: State<'_, Database>,
+) -> Result<GoalStats, String> {
+    let week_start = get_week_start_setting(&db);
+    crate::stats::compute_stats(&db, from, to, &Local::now(), week_start)
+        .map_err(|e| e.to_string())
+}
+
+/// How well the week or month containing `date` lines up with the goals below it
+#[tauri::command]
+pub async fn get_alignment(
+    level: String,
+    date: i64,
+    db: State<'_, Database>,
+) -> Result<AlignmentReport, String> {
+    let goal_level = GoalLevel::from_str(&level)
+        .ok_or_else(|| "Invalid goal level".to_string())?;
+    let dt = chrono::DateTime::from_timestamp_millis(date)
+        .ok_or("Invalid timestamp")?
+        .with_timezone(&Local);
+
+    let week_start = get_week_start_setting(&db);
+    crate::stats::alignment::compute_alignment(&db, goal_level, &dt, week_start)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,17 @@
+use tauri::{AppHandle, State};
+use crate::undo::{self, UndoStack, UndoState};
+
+#[tauri::command]
+pub async fn undo(app: AppHandle) -> Result<UndoState, String> {
+    undo::undo(&app)
+}
+
+#[tauri::command]
+pub async fn redo(app: AppHandle) -> Result<UndoState, String> {
+    undo::redo(&app)
+}
+
+#[tauri::command]
+pub async fn get_undo_state(history: State<'_, UndoStack>) -> Result<UndoState, String> {
+    Ok(history.state())
+}
@@ -1,4 +1,5 @@
-use tauri::{command, Window, LogicalSize, PhysicalPosition};
+use tauri::{command, Window, LogicalSize, PhysicalPosition, State};
+use crate::deep_link::{PendingRoute, PopoverRoute};
 
 #[cfg(target_os = "windows")]
 fn update_window_region(window: &Window) -> Result<(), String> {
@@ -66,3 +67,10 @@ pub async fn resize_popover(window: Window, height: f64) -> Result<(), String> {
 
     Ok(())
 }
+
+/// View requested by a link or launch argument, if any. Taken once by the
+/// popover on load and on `popover-route-requested`.
+#[command]
+pub async fn take_popover_route(pending: State<'_, PendingRoute>) -> Result<Option<PopoverRoute>, String> {
+    Ok(pending.take())
+}
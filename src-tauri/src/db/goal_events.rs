@@ -0,0 +1,258 @@
+use anyhow::Result;
+use rusqlite::{params, Connection, Row};
+use crate::models::{GoalEvent, GoalEventType, GoalLevel};
+use crate::db::Database;
+
+/// Current time in milliseconds, computed inside SQLite so triggers can use it
+const NOW_MS: &str = "CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER)";
+
+/// Create the append-only `goal_events` table and the triggers on `goals`
+/// that record every change to it, whichever code path made the change.
+pub(crate) fn create_goal_events(conn: &Connection) -> Result<()> {
+    conn.execute_batch(&format!(
+        "CREATE TABLE IF NOT EXISTS goal_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            goal_id TEXT NOT NULL,
+            event_type TEXT NOT NULL,
+            level TEXT NOT NULL,
+            period_start INTEGER NOT NULL,
+            old_value TEXT,
+            new_value TEXT,
+            occurred_at INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_goal_events_goal ON goal_events(goal_id, occurred_at);
+        CREATE INDEX IF NOT EXISTS idx_goal_events_period ON goal_events(level, period_start);
+        CREATE INDEX IF NOT EXISTS idx_goal_events_type ON goal_events(event_type, occurred_at);
+
+        CREATE TRIGGER IF NOT EXISTS goal_events_no_update BEFORE UPDATE ON goal_events BEGIN
+            SELECT RAISE(ABORT, 'goal_events is append-only');
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS goal_events_no_delete BEFORE DELETE ON goal_events BEGIN
+            SELECT RAISE(ABORT, 'goal_events is append-only');
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS goals_event_created AFTER INSERT ON goals BEGIN
+            INSERT INTO goal_events (goal_id, event_type, level, period_start, old_value, new_value, occurred_at)
+            VALUES (new.id, 'created', new.level, new.period_start, NULL, new.title, {NOW_MS});
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS goals_event_renamed AFTER UPDATE OF title ON goals
+        WHEN old.title IS NOT new.title BEGIN
+            INSERT INTO goal_events (goal_id, event_type, level, period_start, old_value, new_value, occurred_at)
+            VALUES (new.id, 'renamed', new.level, new.period_start, old.title, new.title, {NOW_MS});
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS goals_event_completion AFTER UPDATE OF is_completed ON goals
+        WHEN old.is_completed IS NOT new.is_completed BEGIN
+            INSERT INTO goal_events (goal_id, event_type, level, period_start, old_value, new_value, occurred_at)
+            VALUES (new.id,
+                    CASE WHEN new.is_completed != 0 THEN 'completed' ELSE 'uncompleted' END,
+                    new.level, new.period_start, old.completed_at, new.completed_at, {NOW_MS});
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS goals_event_relinked AFTER UPDATE OF parent_goal_id ON goals
+        WHEN old.parent_goal_id IS NOT new.parent_goal_id BEGIN
+            INSERT INTO goal_events (goal_id, event_type, level, period_start, old_value, new_value, occurred_at)
+            VALUES (new.id, 'relinked', new.level, new.period_start, old.parent_goal_id, new.parent_goal_id, {NOW_MS});
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS goals_event_moved AFTER UPDATE OF level, period_start ON goals
+        WHEN old.period_start IS NOT new.period_start OR old.level IS NOT new.level BEGIN
+            INSERT INTO goal_events (goal_id, event_type, level, period_start, old_value, new_value, occurred_at)
+            VALUES (new.id, 'moved', new.level, new.period_start, old.period_start, new.period_start, {NOW_MS});
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS goals_event_trashed AFTER UPDATE OF deleted_at ON goals
+        WHEN (old.deleted_at IS NULL) != (new.deleted_at IS NULL) BEGIN
+            INSERT INTO goal_events (goal_id, event_type, level, period_start, old_value, new_value, occurred_at)
+            VALUES (new.id,
+                    CASE WHEN new.deleted_at IS NOT NULL THEN 'deleted' ELSE 'restored' END,
+                    new.level, new.period_start, old.deleted_at, new.deleted_at, {NOW_MS});
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS goals_event_purged AFTER DELETE ON goals BEGIN
+            INSERT INTO goal_events (goal_id, event_type, level, period_start, old_value, new_value, occurred_at)
+            VALUES (old.id, 'purged', old.level, old.period_start, old.title, NULL, {NOW_MS});
+        END;"
+    ))?;
+
+    // Goals from before the log existed get their history from the columns
+    // they still have: created, and completed or deleted when that applies.
+    // SQLite reads the SELECT in full before inserting, so every row is seen
+    // as having no events yet.
+    conn.execute_batch(
+        "INSERT INTO goal_events (goal_id, event_type, level, period_start, old_value, new_value, occurred_at)
+         SELECT id, event_type, level, period_start, NULL, new_value, occurred_at FROM (
+             SELECT id, 'created' AS event_type, level, period_start, title AS new_value,
+                    created_at AS occurred_at, 0 AS step
+             FROM goals
+             UNION ALL
+             SELECT id, 'completed', level, period_start, completed_at, completed_at, 1
+             FROM goals WHERE is_completed != 0 AND completed_at IS NOT NULL
+             UNION ALL
+             SELECT id, 'deleted', level, period_start, deleted_at, deleted_at, 2
+             FROM goals WHERE deleted_at IS NOT NULL
+         ) AS history
+         WHERE NOT EXISTS (SELECT 1 FROM goal_events e WHERE e.goal_id = history.id)
+         ORDER BY occurred_at, step;"
+    )?;
+
+    Ok(())
+}
+
+const EVENT_SELECT: &str =
+    "SELECT e.id, e.goal_id, e.event_type, e.level, e.period_start, e.old_value, e.new_value, e.occurred_at,
+            COALESCE(g.title, (
+                SELECT CASE WHEN t.event_type = 'purged' THEN t.old_value ELSE t.new_value END
+                FROM goal_events t
+                WHERE t.goal_id = e.goal_id AND t.event_type IN ('created', 'renamed', 'purged')
+                ORDER BY t.id DESC LIMIT 1
+            ))
+     FROM goal_events e
+     LEFT JOIN goals g ON g.id = e.goal_id";
+
+fn event_from_row(row: &Row) -> rusqlite::Result<GoalEvent> {
+    Ok(GoalEvent {
+        id: row.get(0)?,
+        goal_id: row.get(1)?,
+        event_type: GoalEventType::from_str(&row.get::<_, String>(2)?)
+            .unwrap_or(GoalEventType::Created),
+        level: GoalLevel::from_str(&row.get::<_, String>(3)?)
+            .unwrap_or(GoalLevel::Daily),
+        period_start: row.get(4)?,
+        old_value: row.get(5)?,
+        new_value: row.get(6)?,
+        occurred_at: row.get(7)?,
+        goal_title: row.get(8)?,
+    })
+}
+
+impl Database {
+    /// Every recorded event for a goal, oldest first
+    pub fn get_goal_events(&self, goal_id: &str) -> Result<Vec<GoalEvent>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "{EVENT_SELECT} WHERE e.goal_id = ? ORDER BY e.occurred_at ASC, e.id ASC"
+        ))?;
+
+        let mut events = Vec::new();
+        for event in stmt.query_map([goal_id], event_from_row)? {
+            events.push(event?);
+        }
+        Ok(events)
+    }
+
+    /// Events for goals of `level` whose period starts in `[from, to)`, oldest first
+    pub fn get_goal_events_in_range(&self, level: &str, from: i64, to: i64) -> Result<Vec<GoalEvent>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "{EVENT_SELECT}
+             WHERE e.level = ? AND e.period_start >= ? AND e.period_start < ?
+             ORDER BY e.occurred_at ASC, e.id ASC"
+        ))?;
+
+        let mut events = Vec::new();
+        for event in stmt.query_map(params![level, from, to], event_from_row)? {
+            events.push(event?);
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Goal;
+
+    fn test_db() -> Database {
+        let db = Database::new(":memory:".into()).unwrap();
+        db.migrate().unwrap();
+        db
+    }
+
+    fn event_types(db: &Database, goal_id: &str) -> Vec<GoalEventType> {
+        db.get_goal_events(goal_id).unwrap().iter().map(|e| e.event_type).collect()
+    }
+
+    #[test]
+    fn test_mutations_write_events() {
+        let db = test_db();
+        let goal = Goal::new("Call bank".into(), GoalLevel::Daily, 1_000, None);
+        db.add_goal(&goal).unwrap();
+        db.update_goal(&goal.id, "Call the bank").unwrap();
+        // 同じタイトルへの更新は記録しない
+        db.update_goal(&goal.id, "Call the bank").unwrap();
+        db.toggle_goal_completion(&goal.id).unwrap();
+        db.toggle_goal_completion(&goal.id).unwrap();
+        db.delete_goal(&goal.id).unwrap();
+        db.restore_goal(&goal.id).unwrap();
+
+        use GoalEventType::*;
+        assert_eq!(
+            event_types(&db, &goal.id),
+            [Created, Renamed, Completed, Uncompleted, Deleted, Restored],
+        );
+
+        let events = db.get_goal_events(&goal.id).unwrap();
+        assert_eq!(events[0].new_value.as_deref(), Some("Call bank"));
+        assert_eq!(
+            (events[1].old_value.as_deref(), events[1].new_value.as_deref()),
+            (Some("Call bank"), Some("Call the bank")),
+        );
+        assert!(events.iter().all(|e| e.goal_title.as_deref() == Some("Call the bank")));
+        assert_eq!(db.get_goal_events_in_range("daily", 0, 2_000).unwrap().len(), 6);
+        assert!(db.get_goal_events_in_range("weekly", 0, 2_000).unwrap().is_empty());
+
+        // 完全削除後もタイトルは最後のイベントから分かる
+        db.delete_goal(&goal.id).unwrap();
+        db.purge_goal(&goal.id).unwrap();
+        let events = db.get_goal_events(&goal.id).unwrap();
+        assert_eq!(events.last().unwrap().event_type, Purged);
+        assert_eq!(events.last().unwrap().goal_title.as_deref(), Some("Call the bank"));
+
+        // 追記のみ
+        let conn = db.conn.lock().unwrap();
+        assert!(conn.execute("DELETE FROM goal_events", []).is_err());
+        assert!(conn.execute("UPDATE goal_events SET event_type = 'created'", []).is_err());
+    }
+
+    #[test]
+    fn test_existing_goals_are_backfilled() {
+        let db = test_db();
+        // イベントログ導入前のデータベースを再現する
+        db.conn.lock().unwrap().execute_batch(
+            "DROP TABLE goal_events;
+             DROP TRIGGER goals_event_created;
+             DROP TRIGGER goals_event_renamed;
+             DROP TRIGGER goals_event_completion;
+             DROP TRIGGER goals_event_relinked;
+             DROP TRIGGER goals_event_moved;
+             DROP TRIGGER goals_event_trashed;
+             DROP TRIGGER goals_event_purged;"
+        ).unwrap();
+        let open = Goal { created_at: 100, ..Goal::new("Open".into(), GoalLevel::Weekly, 0, None) };
+        let done = Goal {
+            created_at: 100,
+            is_completed: true,
+            completed_at: Some(200),
+            deleted_at: Some(300),
+            ..Goal::new("Done".into(), GoalLevel::Weekly, 0, None)
+        };
+        db.write_goal_snapshot(&open).unwrap();
+        db.write_goal_snapshot(&done).unwrap();
+
+        db.migrate().unwrap();
+        use GoalEventType::*;
+        assert_eq!(event_types(&db, &open.id), [Created]);
+        assert_eq!(event_types(&db, &done.id), [Created, Completed, Deleted]);
+        let events = db.get_goal_events(&done.id).unwrap();
+        assert_eq!(events.iter().map(|e| e.occurred_at).collect::<Vec<_>>(), [100, 200, 300]);
+        assert_eq!(events[0].new_value.as_deref(), Some("Done"));
+
+        // 起動のたびに重複して書き込まない
+        db.migrate().unwrap();
+        assert_eq!(db.get_goal_events(&done.id).unwrap().len(), 3);
+    }
+}
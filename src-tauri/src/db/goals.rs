@@ -1,71 +1,129 @@
 use anyhow::Result;
-use rusqlite::params;
+use rusqlite::{params, Connection, OptionalExtension, Row};
 use crate::models::{Goal, GoalLevel};
 use crate::db::Database;
 
+const GOAL_COLUMNS: &str =
+    "id, title, level, is_completed, completed_at, created_at, period_start, parent_goal_id, note, deleted_at";
+
+fn goal_from_row(row: &Row) -> rusqlite::Result<Goal> {
+    Ok(Goal {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        level: GoalLevel::from_str(&row.get::<_, String>(2)?)
+            .unwrap_or(GoalLevel::Daily),
+        is_completed: row.get::<_, i32>(3)? != 0,
+        completed_at: row.get(4)?,
+        created_at: row.get(5)?,
+        period_start: row.get(6)?,
+        parent_goal_id: row.get(7)?,
+        note: row.get(8)?,
+        deleted_at: row.get(9)?,
+    })
+}
+
+/// Overwrite a goal with a previously captured snapshot, recreating the
+/// row if needed (used by undo/redo, inside its transaction)
+pub(crate) fn write_goal_snapshot(conn: &Connection, goal: &Goal) -> Result<()> {
+    conn.execute(
+        "INSERT INTO goals (id, title, level, is_completed, completed_at, created_at, period_start, parent_goal_id, note, deleted_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+         ON CONFLICT(id) DO UPDATE SET
+             title = excluded.title,
+             level = excluded.level,
+             is_completed = excluded.is_completed,
+             completed_at = excluded.completed_at,
+             created_at = excluded.created_at,
+             period_start = excluded.period_start,
+             parent_goal_id = excluded.parent_goal_id,
+             note = excluded.note,
+             deleted_at = excluded.deleted_at",
+        params![
+            &goal.id,
+            &goal.title,
+            goal.level.as_str(),
+            goal.is_completed as i32,
+            goal.completed_at,
+            goal.created_at,
+            goal.period_start,
+            &goal.parent_goal_id,
+            &goal.note,
+            goal.deleted_at,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Remove a goal row outright, bypassing the trash (undoing its creation)
+pub(crate) fn remove_goal(conn: &Connection, id: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE goals SET parent_goal_id = NULL WHERE parent_goal_id = ?",
+        params![id],
+    )?;
+    conn.execute("DELETE FROM goals WHERE id = ?", params![id])?;
+    Ok(())
+}
+
 impl Database {
+    /// Goals that are not in the trash
     pub fn get_goals(&self, level: Option<&str>) -> Result<Vec<Goal>> {
         let conn = self.conn.lock().unwrap();
 
-        let mut stmt = if let Some(level) = level {
-            let mut stmt = conn.prepare(
-                "SELECT id, title, level, is_completed, completed_at, created_at, period_start, parent_goal_id, note
-                 FROM goals WHERE level = ? ORDER BY created_at ASC"
-            )?;
-            let rows = stmt.query_map([level], |row| {
-                Ok(Goal {
-                    id: row.get(0)?,
-                    title: row.get(1)?,
-                    level: GoalLevel::from_str(&row.get::<_, String>(2)?)
-                        .unwrap_or(GoalLevel::Daily),
-                    is_completed: row.get::<_, i32>(3)? != 0,
-                    completed_at: row.get(4)?,
-                    created_at: row.get(5)?,
-                    period_start: row.get(6)?,
-                    parent_goal_id: row.get(7)?,
-                    note: row.get(8)?,
-                })
-            })?;
-
-            let mut goals = Vec::new();
-            for goal in rows {
+        let mut goals = Vec::new();
+        if let Some(level) = level {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {GOAL_COLUMNS} FROM goals
+                 WHERE level = ? AND deleted_at IS NULL ORDER BY created_at ASC"
+            ))?;
+            for goal in stmt.query_map([level], goal_from_row)? {
                 goals.push(goal?);
             }
-            return Ok(goals);
         } else {
-            conn.prepare(
-                "SELECT id, title, level, is_completed, completed_at, created_at, period_start, parent_goal_id, note
-                 FROM goals ORDER BY created_at ASC"
-            )?
-        };
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {GOAL_COLUMNS} FROM goals
+                 WHERE deleted_at IS NULL ORDER BY created_at ASC"
+            ))?;
+            for goal in stmt.query_map([], goal_from_row)? {
+                goals.push(goal?);
+            }
+        }
+        Ok(goals)
+    }
 
-        let rows = stmt.query_map([], |row| {
-            Ok(Goal {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                level: GoalLevel::from_str(&row.get::<_, String>(2)?)
-                    .unwrap_or(GoalLevel::Daily),
-                is_completed: row.get::<_, i32>(3)? != 0,
-                completed_at: row.get(4)?,
-                created_at: row.get(5)?,
-                period_start: row.get(6)?,
-                parent_goal_id: row.get(7)?,
-                note: row.get(8)?,
-            })
-        })?;
+    /// Goals of `level` (not in the trash) whose period starts in `[from, to)`
+    pub fn get_goals_in_range(&self, level: &str, from: i64, to: i64) -> Result<Vec<Goal>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {GOAL_COLUMNS} FROM goals
+             WHERE level = ? AND period_start >= ? AND period_start < ? AND deleted_at IS NULL
+             ORDER BY created_at ASC"
+        ))?;
 
         let mut goals = Vec::new();
-        for goal in rows {
+        for goal in stmt.query_map(params![level, from, to], goal_from_row)? {
             goals.push(goal?);
         }
         Ok(goals)
     }
 
+    /// Look up a single goal, including trashed ones
+    pub fn get_goal(&self, id: &str) -> Result<Option<Goal>> {
+        let conn = self.conn.lock().unwrap();
+        let goal = conn
+            .query_row(
+                &format!("SELECT {GOAL_COLUMNS} FROM goals WHERE id = ?"),
+                [id],
+                goal_from_row,
+            )
+            .optional()?;
+        Ok(goal)
+    }
+
     pub fn add_goal(&self, goal: &Goal) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "INSERT INTO goals (id, title, level, is_completed, completed_at, created_at, period_start, parent_goal_id, note)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            "INSERT INTO goals (id, title, level, is_completed, completed_at, created_at, period_start, parent_goal_id, note, deleted_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
                 &goal.id,
                 &goal.title,
@@ -76,6 +134,7 @@ impl Database {
                 goal.period_start,
                 &goal.parent_goal_id,
                 &goal.note,
+                goal.deleted_at,
             ],
         )?;
         Ok(())
@@ -112,38 +171,207 @@ impl Database {
         )?;
 
         // Return updated goal
-        let mut stmt = conn.prepare(
-            "SELECT id, title, level, is_completed, completed_at, created_at, period_start, parent_goal_id, note
-             FROM goals WHERE id = ?"
+        let goal = conn.query_row(
+            &format!("SELECT {GOAL_COLUMNS} FROM goals WHERE id = ?"),
+            [id],
+            goal_from_row,
         )?;
 
-        let goal = stmt.query_row([id], |row| {
-            Ok(Goal {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                level: GoalLevel::from_str(&row.get::<_, String>(2)?)
-                    .unwrap_or(GoalLevel::Daily),
-                is_completed: row.get::<_, i32>(3)? != 0,
-                completed_at: row.get(4)?,
-                created_at: row.get(5)?,
-                period_start: row.get(6)?,
-                parent_goal_id: row.get(7)?,
-                note: row.get(8)?,
-            })
-        })?;
-
         Ok(goal)
     }
 
+    /// Move a goal to the trash. Children keep their link so that restoring
+    /// the parent brings the hierarchy back as it was.
     pub fn delete_goal(&self, id: &str) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-        // 親目標の削除時は子のリンクを解除する（子目標自体は履歴として残す）。
-        // FK の有効/無効やスキーマの CASCADE 定義に依存しないよう明示的に行う
         conn.execute(
-            "UPDATE goals SET parent_goal_id = NULL WHERE parent_goal_id = ?",
+            "UPDATE goals SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL",
+            params![chrono::Utc::now().timestamp_millis(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Overwrite a goal with a previously captured snapshot, recreating the
+    /// row if needed (used by undo/redo)
+    pub fn write_goal_snapshot(&self, goal: &Goal) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        write_goal_snapshot(&conn, goal)
+    }
+
+    /// Remove a goal row outright, bypassing the trash (undoing its creation)
+    pub fn remove_goal(&self, id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        remove_goal(&conn, id)
+    }
+
+    /// Goals in the trash, most recently deleted first
+    pub fn get_trashed_goals(&self) -> Result<Vec<Goal>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {GOAL_COLUMNS} FROM goals
+             WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC"
+        ))?;
+
+        let mut goals = Vec::new();
+        for goal in stmt.query_map([], goal_from_row)? {
+            goals.push(goal?);
+        }
+        Ok(goals)
+    }
+
+    /// Take a goal out of the trash. A parent that is still in the trash is
+    /// unlinked, so live goals never point at trashed ones.
+    pub fn restore_goal(&self, id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE goals SET
+                 deleted_at = NULL,
+                 parent_goal_id = CASE
+                     WHEN EXISTS (SELECT 1 FROM goals p WHERE p.id = goals.parent_goal_id AND p.deleted_at IS NULL)
+                     THEN parent_goal_id
+                 END
+             WHERE id = ?",
             params![id],
         )?;
-        conn.execute("DELETE FROM goals WHERE id = ?", params![id])?;
         Ok(())
     }
+
+    /// Permanently delete a trashed goal. Goals outside the trash are left
+    /// alone; returns whether a goal was purged.
+    pub fn purge_goal(&self, id: &str) -> Result<bool> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let purged = tx.execute(
+            "DELETE FROM goals WHERE id = ? AND deleted_at IS NOT NULL",
+            params![id],
+        )? > 0;
+        // 親目標の削除時は子のリンクを解除する（子目標自体は履歴として残す）。
+        // FK の有効/無効やスキーマの CASCADE 定義に依存しないよう明示的に行う
+        if purged {
+            tx.execute(
+                "UPDATE goals SET parent_goal_id = NULL WHERE parent_goal_id = ?",
+                params![id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(purged)
+    }
+
+    /// Permanently delete goals trashed before `cutoff` (milliseconds).
+    /// Returns the number of purged goals.
+    pub fn purge_trash_before(&self, cutoff: i64) -> Result<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "UPDATE goals SET parent_goal_id = NULL WHERE parent_goal_id IN
+                (SELECT id FROM goals WHERE deleted_at IS NOT NULL AND deleted_at < ?)",
+            params![cutoff],
+        )?;
+        let purged = tx.execute(
+            "DELETE FROM goals WHERE deleted_at IS NOT NULL AND deleted_at < ?",
+            params![cutoff],
+        )?;
+        tx.commit()?;
+        Ok(purged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_db() -> Database {
+        let db = Database::new(":memory:".into()).unwrap();
+        db.migrate().unwrap();
+        db
+    }
+
+    fn add(db: &Database, title: &str, level: GoalLevel, parent: Option<&Goal>) -> Goal {
+        let goal = Goal::new(title.to_string(), level, 0, parent.map(|p| p.id.clone()));
+        db.add_goal(&goal).unwrap();
+        goal
+    }
+
+    /// Put a goal in the trash as if it had been deleted at `at`
+    fn trash_at(db: &Database, goal: &Goal, at: i64) {
+        db.write_goal_snapshot(&Goal { deleted_at: Some(at), ..goal.clone() }).unwrap();
+    }
+
+    #[test]
+    fn test_trash_and_restore() {
+        let db = test_db();
+        let parent = add(&db, "Launch", GoalLevel::Weekly, None);
+        let child = add(&db, "Write copy", GoalLevel::Daily, Some(&parent));
+
+        db.delete_goal(&parent.id).unwrap();
+        assert!(db.get_goals(None).unwrap().iter().all(|g| g.id != parent.id));
+        assert_eq!(db.get_trashed_goals().unwrap()[0].id, parent.id);
+
+        // 親を戻すと子のリンクもそのまま
+        db.restore_goal(&parent.id).unwrap();
+        assert!(db.get_trashed_goals().unwrap().is_empty());
+        assert_eq!(db.get_goal(&child.id).unwrap().unwrap().parent_goal_id, Some(parent.id.clone()));
+
+        // 親がゴミ箱にある間に子だけ戻すとリンクを外す
+        db.delete_goal(&child.id).unwrap();
+        db.delete_goal(&parent.id).unwrap();
+        db.restore_goal(&child.id).unwrap();
+        let restored = db.get_goal(&child.id).unwrap().unwrap();
+        assert_eq!((restored.deleted_at, restored.parent_goal_id), (None, None));
+    }
+
+    #[test]
+    fn test_purge_goal() {
+        let db = test_db();
+        let parent = add(&db, "Launch", GoalLevel::Weekly, None);
+        let child = add(&db, "Write copy", GoalLevel::Daily, Some(&parent));
+
+        // ゴミ箱にない目標は消さず、子のリンクも残す
+        assert!(!db.purge_goal(&parent.id).unwrap());
+        assert!(db.get_goal(&parent.id).unwrap().is_some());
+        assert_eq!(db.get_goal(&child.id).unwrap().unwrap().parent_goal_id, Some(parent.id.clone()));
+
+        db.delete_goal(&parent.id).unwrap();
+        assert!(db.purge_goal(&parent.id).unwrap());
+        assert!(db.get_goal(&parent.id).unwrap().is_none());
+        assert_eq!(db.get_goal(&child.id).unwrap().unwrap().parent_goal_id, None);
+    }
+
+    #[test]
+    fn test_purge_trash_before() {
+        let db = test_db();
+        let old = add(&db, "Old", GoalLevel::Weekly, None);
+        let recent = add(&db, "Recent", GoalLevel::Weekly, None);
+        let live = add(&db, "Live", GoalLevel::Weekly, None);
+        let child = add(&db, "Child of old", GoalLevel::Daily, Some(&old));
+        trash_at(&db, &old, 1_000);
+        trash_at(&db, &recent, 5_000);
+
+        // 境界ちょうどの目標は残す
+        assert_eq!(db.purge_trash_before(5_000).unwrap(), 1);
+        assert!(db.get_goal(&old.id).unwrap().is_none());
+        assert!(db.get_goal(&recent.id).unwrap().is_some());
+        assert!(db.get_goal(&live.id).unwrap().is_some());
+        assert_eq!(db.get_goal(&child.id).unwrap().unwrap().parent_goal_id, None);
+
+        assert_eq!(db.purge_trash_before(i64::MAX).unwrap(), 1);
+        assert!(db.get_trashed_goals().unwrap().is_empty());
+        assert_eq!(db.get_goals(None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_purge_expired_trash_uses_retention_setting() {
+        let db = test_db();
+        let day = 24 * 60 * 60 * 1000;
+        let now = chrono::Utc::now().timestamp_millis();
+        let expired = add(&db, "Expired", GoalLevel::Daily, None);
+        let kept = add(&db, "Kept", GoalLevel::Daily, None);
+        trash_at(&db, &expired, now - 8 * day);
+        trash_at(&db, &kept, now - 6 * day);
+
+        db.set_setting("trash_retention_days", "7").unwrap();
+        assert_eq!(crate::commands::goals::purge_expired_trash(&db).unwrap(), 1);
+        assert!(db.get_goal(&expired.id).unwrap().is_none());
+        assert!(db.get_goal(&kept.id).unwrap().is_some());
+    }
 }
@@ -0,0 +1,87 @@
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use crate::models::JobState;
+use crate::db::Database;
+
+/// Create the table holding the last run of every scheduled job. The
+/// reflection check used to keep its last run in the settings table.
+pub(crate) fn create_job_runs(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS job_runs (
+            job_id TEXT PRIMARY KEY,
+            last_run_at INTEGER,
+            next_run_at INTEGER,
+            last_error TEXT,
+            run_count INTEGER NOT NULL DEFAULT 0
+        );
+
+        INSERT OR IGNORE INTO job_runs (job_id, last_run_at)
+        SELECT 'reflection_prompts', CAST(value AS INTEGER)
+        FROM settings WHERE key = 'last_period_check_timestamp' AND value != '0';
+
+        DELETE FROM settings WHERE key = 'last_period_check_timestamp';",
+    )?;
+
+    Ok(())
+}
+
+fn job_state_from_row(row: &Row) -> rusqlite::Result<JobState> {
+    Ok(JobState {
+        job_id: row.get(0)?,
+        last_run_at: row.get(1)?,
+        next_run_at: row.get(2)?,
+        last_error: row.get(3)?,
+        run_count: row.get(4)?,
+    })
+}
+
+impl Database {
+    pub fn get_job_state(&self, job_id: &str) -> Result<Option<JobState>> {
+        let conn = self.conn.lock().unwrap();
+        let state = conn
+            .query_row(
+                "SELECT job_id, last_run_at, next_run_at, last_error, run_count
+                 FROM job_runs WHERE job_id = ?",
+                [job_id],
+                job_state_from_row,
+            )
+            .optional()?;
+        Ok(state)
+    }
+
+    pub fn get_job_states(&self) -> Result<Vec<JobState>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT job_id, last_run_at, next_run_at, last_error, run_count
+             FROM job_runs ORDER BY job_id"
+        )?;
+
+        let mut states = Vec::new();
+        for state in stmt.query_map([], job_state_from_row)? {
+            states.push(state?);
+        }
+        Ok(states)
+    }
+
+    /// Record that a job ran at `ran_at`
+    pub fn record_job_run(
+        &self,
+        job_id: &str,
+        ran_at: i64,
+        next_run_at: Option<i64>,
+        error: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO job_runs (job_id, last_run_at, next_run_at, last_error, run_count)
+             VALUES (?, ?, ?, ?, 1)
+             ON CONFLICT(job_id) DO UPDATE SET
+                 last_run_at = excluded.last_run_at,
+                 next_run_at = excluded.next_run_at,
+                 last_error = excluded.last_error,
+                 run_count = run_count + 1",
+            params![job_id, ran_at, next_run_at, error],
+        )?;
+        Ok(())
+    }
+}
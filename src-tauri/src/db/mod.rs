@@ -1,12 +1,16 @@
 use anyhow::Result;
 use rusqlite::{Connection, params};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Manager, Runtime};
 
 pub mod goals;
 pub mod settings;
 pub mod reflections;
+pub mod search;
+pub mod goal_events;
+pub mod stats;
+pub mod jobs;
 
 #[derive(Clone)]
 pub struct Database {
@@ -20,11 +24,49 @@ impl Database {
             conn: Arc::new(Mutex::new(conn)),
         })
     }
+
+    /// Create or upgrade the schema. Safe to run on every startup.
+    pub fn migrate(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        run_migrations(&conn)
+    }
+
+    /// Run `f` in a single transaction, rolled back if it fails
+    pub fn in_transaction<T>(&self, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let result = f(&tx)?;
+        tx.commit()?;
+        Ok(result)
+    }
+
+    /// Write a consistent copy of the database to `path`, which must not exist yet
+    pub fn backup_to(&self, path: &Path) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("VACUUM INTO ?", [path.to_string_lossy()])?;
+        Ok(())
+    }
 }
 
-pub async fn init_database(app: &AppHandle) -> Result<()> {
-    let app_dir = app.path().app_data_dir()
-        .expect("Failed to get app data directory");
+/// Directory holding trivyn.db, backups and review reports
+pub struct DataDir(pub PathBuf);
+
+/// The data directory in use: `--data-dir` when given, otherwise the
+/// platform's app data directory
+pub fn data_dir<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<PathBuf> {
+    match app.try_state::<DataDir>() {
+        Some(dir) => Ok(dir.0.clone()),
+        None => app.path().app_data_dir(),
+    }
+}
+
+/// Open trivyn.db in `data_dir`, or in the app data directory when `None`
+pub async fn init_database(app: &AppHandle, data_dir: Option<PathBuf>) -> Result<()> {
+    let app_dir = match data_dir {
+        Some(dir) => dir,
+        None => app.path().app_data_dir()
+            .expect("Failed to get app data directory"),
+    };
 
     if !app_dir.exists() {
         std::fs::create_dir_all(&app_dir)?;
@@ -34,7 +76,16 @@ pub async fn init_database(app: &AppHandle) -> Result<()> {
     let db = Database::new(db_path)?;
 
     // Run migrations
-    let conn = db.conn.lock().unwrap();
+    db.migrate()?;
+
+    // Store database in app state
+    app.manage(db);
+    app.manage(DataDir(app_dir));
+
+    Ok(())
+}
+
+fn run_migrations(conn: &Connection) -> Result<()> {
 
     // Create goals table
     conn.execute(
@@ -53,6 +104,14 @@ pub async fn init_database(app: &AppHandle) -> Result<()> {
         [],
     )?;
 
+    // Soft delete: trashed goals keep their row until purged
+    add_column_if_missing(conn, "goals", "deleted_at", "INTEGER")?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_goals_deleted_at ON goals(deleted_at)",
+        [],
+    )?;
+
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_goals_level ON goals(level)",
         [],
@@ -68,6 +127,16 @@ pub async fn init_database(app: &AppHandle) -> Result<()> {
         [],
     )?;
 
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_goals_level_period_start ON goals(level, period_start)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_goals_completed_at ON goals(completed_at)",
+        [],
+    )?;
+
     // Create settings table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS settings (
@@ -112,15 +181,98 @@ pub async fn init_database(app: &AppHandle) -> Result<()> {
     )?;
     conn.execute(
         "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
-        params!["last_period_check_timestamp", "0"],
+        params!["last_weekly_reflection_prompt", ""],
     )?;
     conn.execute(
         "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
-        params!["last_weekly_reflection_prompt", ""],
+        params!["last_monthly_reflection_prompt", ""],
     )?;
     conn.execute(
         "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
-        params!["last_monthly_reflection_prompt", ""],
+        params!["trash_retention_days", "30"],
+    )?;
+    // End-of-day shutdown review, "HH:MM" local time (empty disables it)
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
+        params!["daily_reflection_time", "21:00"],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
+        params!["last_daily_reflection_prompt", ""],
+    )?;
+    // Reminder notifications: snooze and pause ends (ms, 0 = none), the week
+    // reminders were skipped for, quiet hours (JSON array of QuietWindow),
+    // reminders held back for later and the last reminder shown
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
+        params!["reminder_snoozed_until", "0"],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
+        params!["reminders_paused_until", "0"],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
+        params!["quiet_hours", "[]"],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
+        params!["pending_reminders", "[]"],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
+        params!["reminder_skipped_week", ""],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
+        params!["last_reminder_notification", ""],
+    )?;
+    // Kind of the reminder shown last that the user has not acted on yet
+    // ("" when none), offered in the tray menu
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
+        params!["open_reminder", ""],
+    )?;
+    // Planning reminders: nudge at planning_reminder_time when a period has
+    // fewer than planning_min_goals goals, and at planning_wrap_up_time on
+    // the last day of a period with open goals ("" disables either)
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
+        params!["planning_reminder_enabled", "true"],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
+        params!["planning_reminder_time", "09:00"],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
+        params!["planning_wrap_up_time", "17:00"],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
+        params!["planning_min_goals", "3"],
+    )?;
+    // Tray menu: list this week's goals below today's
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
+        params!["tray_show_weekly_goals", "false"],
+    )?;
+    // Global shortcuts (accelerators); an empty value disables one
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
+        params!["hotkey_toggle_floating", "CommandOrControl+Alt+F"],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
+        params!["hotkey_open_popover", "CommandOrControl+Alt+P"],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
+        params!["hotkey_quick_add", "CommandOrControl+Alt+N"],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?, ?)",
+        params!["hotkey_start_reflection", "CommandOrControl+Alt+R"],
     )?;
 
     // Create reflections table
@@ -138,10 +290,62 @@ pub async fn init_database(app: &AppHandle) -> Result<()> {
         [],
     )?;
 
-    drop(conn);
+    // Optional reflection details. Existing rows keep their insights and
+    // simply have no rating, notes or prompt answers.
+    add_column_if_missing(conn, "reflections", "rating", "INTEGER")?;
+    add_column_if_missing(conn, "reflections", "mood", "INTEGER")?;
+    add_column_if_missing(conn, "reflections", "energy", "INTEGER")?;
+    add_column_if_missing(conn, "reflections", "body", "TEXT")?;
+    add_column_if_missing(conn, "reflections", "answers", "TEXT")?;
+    add_column_if_missing(conn, "reflections", "updated_at", "INTEGER")?;
+    conn.execute(
+        "UPDATE reflections SET updated_at = created_at WHERE updated_at IS NULL",
+        [],
+    )?;
 
-    // Store database in app state
-    app.manage(db);
+    // Previous content of edited reflections
+    reflections::create_reflection_revisions(conn)?;
+
+    // User-defined reflection prompts per level
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS reflection_prompts (
+            id TEXT PRIMARY KEY,
+            level TEXT NOT NULL,
+            text TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            archived_at INTEGER
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_reflection_prompts_level ON reflection_prompts(level, position)",
+        [],
+    )?;
+
+    // Full-text search index over goals and reflections
+    search::create_search_index(conn)?;
+
+    // Append-only activity log of goal changes
+    goal_events::create_goal_events(conn)?;
 
+    // Last runs of scheduled background jobs
+    jobs::create_job_runs(conn)?;
+
+    // The weekly reflection prompt remembered its week as an ISO week number
+    settings::migrate_weekly_prompt_key(conn)?;
+
+    Ok(())
+}
+
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    let exists: bool = conn.query_row(
+        &format!("SELECT EXISTS(SELECT 1 FROM pragma_table_info('{table}') WHERE name = ?)"),
+        [column],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        conn.execute(&format!("ALTER TABLE {table} ADD COLUMN {column} {definition}"), [])?;
+    }
     Ok(())
 }
@@ -1,30 +1,159 @@
 use anyhow::Result;
-use rusqlite::params;
-use crate::models::{Reflection, GoalLevel};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use crate::commands::periods::{period_key, period_key_start};
+use crate::models::{Reflection, ReflectionDetails, ReflectionPrompt, ReflectionRevision, GoalLevel};
 use crate::db::Database;
 
+const REFLECTION_COLUMNS: &str =
+    "id, level, period_key, insight_1, insight_2, insight_3, rating, mood, energy, body, answers, created_at, updated_at";
+
+/// Create the side table that keeps the previous content of a reflection
+/// every time it is edited
+pub(crate) fn create_reflection_revisions(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS reflection_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            reflection_id INTEGER NOT NULL,
+            insight_1 TEXT,
+            insight_2 TEXT,
+            insight_3 TEXT,
+            rating INTEGER,
+            mood INTEGER,
+            energy INTEGER,
+            body TEXT,
+            answers TEXT,
+            revised_at INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_reflection_revisions_reflection
+            ON reflection_revisions(reflection_id, revised_at);
+
+        CREATE TRIGGER IF NOT EXISTS reflections_revision AFTER UPDATE ON reflections
+        WHEN old.insight_1 IS NOT new.insight_1
+          OR old.insight_2 IS NOT new.insight_2
+          OR old.insight_3 IS NOT new.insight_3
+          OR old.rating IS NOT new.rating
+          OR old.mood IS NOT new.mood
+          OR old.energy IS NOT new.energy
+          OR old.body IS NOT new.body
+          OR old.answers IS NOT new.answers
+        BEGIN
+            INSERT INTO reflection_revisions (reflection_id, insight_1, insight_2, insight_3,
+                                              rating, mood, energy, body, answers, revised_at)
+            VALUES (old.id, old.insight_1, old.insight_2, old.insight_3,
+                    old.rating, old.mood, old.energy, old.body, old.answers,
+                    COALESCE(new.updated_at, old.updated_at, old.created_at));
+        END;",
+    )?;
+
+    Ok(())
+}
+
+/// Rating, mood, energy, body and answers, starting at column `first`
+fn details_from_row(row: &Row, first: usize) -> rusqlite::Result<ReflectionDetails> {
+    Ok(ReflectionDetails {
+        rating: row.get(first)?,
+        mood: row.get(first + 1)?,
+        energy: row.get(first + 2)?,
+        body: row.get(first + 3)?,
+        answers: row
+            .get::<_, Option<String>>(first + 4)?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default(),
+    })
+}
+
+fn reflection_from_row(row: &Row) -> rusqlite::Result<Reflection> {
+    Ok(Reflection {
+        id: row.get(0)?,
+        level: GoalLevel::from_str(&row.get::<_, String>(1)?)
+            .unwrap_or(GoalLevel::Daily),
+        period_key: row.get(2)?,
+        insight_1: row.get(3)?,
+        insight_2: row.get(4)?,
+        insight_3: row.get(5)?,
+        details: details_from_row(row, 6)?,
+        created_at: row.get(11)?,
+        updated_at: row.get(12)?,
+    })
+}
+
+/// Answers are stored as a JSON object, or NULL when there are none
+fn answers_json(details: &ReflectionDetails) -> Option<String> {
+    if details.answers.is_empty() {
+        None
+    } else {
+        serde_json::to_string(&details.answers).ok()
+    }
+}
+
+fn prompt_from_row(row: &Row) -> rusqlite::Result<ReflectionPrompt> {
+    Ok(ReflectionPrompt {
+        id: row.get(0)?,
+        level: GoalLevel::from_str(&row.get::<_, String>(1)?)
+            .unwrap_or(GoalLevel::Daily),
+        text: row.get(2)?,
+        position: row.get(3)?,
+        archived_at: row.get(4)?,
+    })
+}
+
+/// Overwrite a reflection with a previously captured snapshot, keeping
+/// its original id (used by undo/redo, inside its transaction)
+pub(crate) fn write_reflection_snapshot(conn: &Connection, reflection: &Reflection) -> Result<()> {
+    conn.execute(
+        "INSERT INTO reflections (id, level, period_key, insight_1, insight_2, insight_3,
+                                  rating, mood, energy, body, answers, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(level, period_key) DO UPDATE SET
+             insight_1 = excluded.insight_1,
+             insight_2 = excluded.insight_2,
+             insight_3 = excluded.insight_3,
+             rating = excluded.rating,
+             mood = excluded.mood,
+             energy = excluded.energy,
+             body = excluded.body,
+             answers = excluded.answers,
+             created_at = excluded.created_at,
+             updated_at = excluded.updated_at",
+        params![
+            reflection.id,
+            reflection.level.as_str(),
+            reflection.period_key,
+            reflection.insight_1,
+            reflection.insight_2,
+            reflection.insight_3,
+            reflection.details.rating,
+            reflection.details.mood,
+            reflection.details.energy,
+            reflection.details.body,
+            answers_json(&reflection.details),
+            reflection.created_at,
+            reflection.updated_at,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Delete the reflection for a specific level and period
+pub(crate) fn delete_reflection(conn: &Connection, level: &str, period_key: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM reflections WHERE level = ? AND period_key = ?",
+        params![level, period_key],
+    )?;
+    Ok(())
+}
+
 impl Database {
     /// Get reflection for a specific level and period
     pub fn get_reflection(&self, level: &str, period_key: &str) -> Result<Option<Reflection>> {
         let conn = self.conn.lock().unwrap();
 
-        let mut stmt = conn.prepare(
-            "SELECT id, level, period_key, insight_1, insight_2, insight_3, created_at
-             FROM reflections WHERE level = ? AND period_key = ?"
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {REFLECTION_COLUMNS} FROM reflections WHERE level = ? AND period_key = ?"
+        ))?;
 
-        let result = stmt.query_row([level, period_key], |row| {
-            Ok(Reflection {
-                id: row.get(0)?,
-                level: GoalLevel::from_str(&row.get::<_, String>(1)?)
-                    .unwrap_or(GoalLevel::Daily),
-                period_key: row.get(2)?,
-                insight_1: row.get(3)?,
-                insight_2: row.get(4)?,
-                insight_3: row.get(5)?,
-                created_at: row.get(6)?,
-            })
-        });
+        let result = stmt.query_row([level, period_key], reflection_from_row);
 
         match result {
             Ok(reflection) => Ok(Some(reflection)),
@@ -33,67 +162,363 @@ impl Database {
         }
     }
 
-    /// Save or update reflection
+    /// Save or update reflection. An existing reflection keeps its id and
+    /// `created_at`; the stored row is returned as it is after the save.
     pub fn save_reflection(&self, reflection: &Reflection) -> Result<Reflection> {
         let conn = self.conn.lock().unwrap();
 
-        conn.execute(
-            "INSERT INTO reflections (level, period_key, insight_1, insight_2, insight_3, created_at)
-             VALUES (?, ?, ?, ?, ?, ?)
-             ON CONFLICT(level, period_key) DO UPDATE SET
-                 insight_1 = excluded.insight_1,
-                 insight_2 = excluded.insight_2,
-                 insight_3 = excluded.insight_3",
+        let saved = conn.query_row(
+            &format!(
+                "INSERT INTO reflections (level, period_key, insight_1, insight_2, insight_3,
+                                          rating, mood, energy, body, answers, created_at, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                 ON CONFLICT(level, period_key) DO UPDATE SET
+                     insight_1 = excluded.insight_1,
+                     insight_2 = excluded.insight_2,
+                     insight_3 = excluded.insight_3,
+                     rating = excluded.rating,
+                     mood = excluded.mood,
+                     energy = excluded.energy,
+                     body = excluded.body,
+                     answers = excluded.answers,
+                     updated_at = excluded.updated_at
+                 RETURNING {REFLECTION_COLUMNS}"
+            ),
             params![
                 reflection.level.as_str(),
                 reflection.period_key,
                 reflection.insight_1,
                 reflection.insight_2,
                 reflection.insight_3,
+                reflection.details.rating,
+                reflection.details.mood,
+                reflection.details.energy,
+                reflection.details.body,
+                answers_json(&reflection.details),
                 reflection.created_at,
+                reflection.updated_at,
             ],
+            reflection_from_row,
         )?;
 
-        let id = conn.last_insert_rowid();
+        Ok(saved)
+    }
+
+    /// Overwrite a reflection with a previously captured snapshot, keeping
+    /// its original id (used by undo/redo)
+    pub fn write_reflection_snapshot(&self, reflection: &Reflection) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        write_reflection_snapshot(&conn, reflection)
+    }
 
-        Ok(Reflection {
-            id: Some(id),
-            level: reflection.level.clone(),
-            period_key: reflection.period_key.clone(),
-            insight_1: reflection.insight_1.clone(),
-            insight_2: reflection.insight_2.clone(),
-            insight_3: reflection.insight_3.clone(),
-            created_at: reflection.created_at,
-        })
+    /// Delete the reflection for a specific level and period
+    pub fn delete_reflection(&self, level: &str, period_key: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        delete_reflection(&conn, level, period_key)
     }
 
-    /// Get all reflections for a specific level
+    /// Get all reflections for a specific level, latest period first
     pub fn get_reflections_by_level(&self, level: &str) -> Result<Vec<Reflection>> {
         let conn = self.conn.lock().unwrap();
 
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {REFLECTION_COLUMNS} FROM reflections WHERE level = ? ORDER BY period_key DESC"
+        ))?;
+
+        let rows = stmt.query_map([level], reflection_from_row)?;
+
+        let mut reflections = Vec::new();
+        for reflection in rows {
+            reflections.push(reflection?);
+        }
+
+        Ok(reflections)
+    }
+
+    /// Reflections of a level whose period key lies in `[from_key, to_key]`,
+    /// oldest first. Keys of one level sort chronologically as strings.
+    pub fn get_reflections_in_key_range(&self, level: &str, from_key: &str, to_key: &str) -> Result<Vec<Reflection>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {REFLECTION_COLUMNS} FROM reflections
+             WHERE level = ? AND period_key >= ? AND period_key <= ?
+             ORDER BY period_key ASC"
+        ))?;
+
+        let mut reflections = Vec::new();
+        for reflection in stmt.query_map(params![level, from_key, to_key], reflection_from_row)? {
+            reflections.push(reflection?);
+        }
+        Ok(reflections)
+    }
+
+    /// Reflections of `level` for every period overlapping `[from, to]`
+    /// (timestamps in milliseconds), oldest period first
+    pub fn get_reflections_in_period_range(
+        &self,
+        level: GoalLevel,
+        from: i64,
+        to: i64,
+        week_start: i32,
+    ) -> Result<Vec<Reflection>> {
+        let (Some(from_key), Some(to_key)) = (
+            period_key(level, from, week_start),
+            period_key(level, to, week_start),
+        ) else {
+            anyhow::bail!("Invalid timestamp range");
+        };
+
+        self.get_reflections_in_key_range(level.as_str(), &from_key, &to_key)
+    }
+
+    /// Reflections of all levels for periods overlapping `[from, to]`, ordered
+    /// by period start. A month comes before its first week and day.
+    pub fn get_reflection_timeline(&self, from: i64, to: i64, week_start: i32) -> Result<Vec<Reflection>> {
+        let mut timeline = Vec::new();
+        for level in [GoalLevel::Monthly, GoalLevel::Weekly, GoalLevel::Daily] {
+            timeline.extend(self.get_reflections_in_period_range(level, from, to, week_start)?);
+        }
+
+        let rank = |level: GoalLevel| match level {
+            GoalLevel::Monthly => 0,
+            GoalLevel::Weekly => 1,
+            GoalLevel::Daily => 2,
+        };
+        timeline.sort_by_key(|r| {
+            (period_key_start(r.level, &r.period_key, week_start), rank(r.level))
+        });
+
+        Ok(timeline)
+    }
+
+    /// Earlier versions of the reflection for a level and period, newest first
+    pub fn get_reflection_revisions(&self, level: &str, period_key: &str) -> Result<Vec<ReflectionRevision>> {
+        let conn = self.conn.lock().unwrap();
+
         let mut stmt = conn.prepare(
-            "SELECT id, level, period_key, insight_1, insight_2, insight_3, created_at
-             FROM reflections WHERE level = ? ORDER BY created_at DESC"
+            "SELECT v.id, v.reflection_id, v.insight_1, v.insight_2, v.insight_3,
+                    v.rating, v.mood, v.energy, v.body, v.answers, v.revised_at
+             FROM reflection_revisions v
+             JOIN reflections r ON r.id = v.reflection_id
+             WHERE r.level = ? AND r.period_key = ?
+             ORDER BY v.revised_at DESC, v.id DESC"
         )?;
 
-        let rows = stmt.query_map([level], |row| {
-            Ok(Reflection {
+        let rows = stmt.query_map([level, period_key], |row| {
+            Ok(ReflectionRevision {
                 id: row.get(0)?,
-                level: GoalLevel::from_str(&row.get::<_, String>(1)?)
-                    .unwrap_or(GoalLevel::Daily),
-                period_key: row.get(2)?,
-                insight_1: row.get(3)?,
-                insight_2: row.get(4)?,
-                insight_3: row.get(5)?,
-                created_at: row.get(6)?,
+                reflection_id: row.get(1)?,
+                insight_1: row.get(2)?,
+                insight_2: row.get(3)?,
+                insight_3: row.get(4)?,
+                details: details_from_row(row, 5)?,
+                revised_at: row.get(10)?,
             })
         })?;
 
-        let mut reflections = Vec::new();
-        for reflection in rows {
-            reflections.push(reflection?);
+        let mut revisions = Vec::new();
+        for revision in rows {
+            revisions.push(revision?);
         }
+        Ok(revisions)
+    }
 
-        Ok(reflections)
+    /// Reflection prompts of a level in display order. Archived prompts are
+    /// only included on request, e.g. to label answers of older reflections.
+    pub fn get_reflection_prompts(&self, level: &str, include_archived: bool) -> Result<Vec<ReflectionPrompt>> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT id, level, text, position, archived_at
+             FROM reflection_prompts
+             WHERE level = ? AND (? OR archived_at IS NULL)
+             ORDER BY archived_at IS NOT NULL, position ASC"
+        )?;
+
+        let mut prompts = Vec::new();
+        for prompt in stmt.query_map(params![level, include_archived], prompt_from_row)? {
+            prompts.push(prompt?);
+        }
+        Ok(prompts)
+    }
+
+    /// Replace the prompt set of a level. Prompts keep their ids so existing
+    /// answers stay attached; prompts missing from `prompts` are archived.
+    /// Ids of another level's prompts are rejected.
+    pub fn set_reflection_prompts(&self, level: &str, prompts: &[ReflectionPrompt]) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let now = chrono::Utc::now().timestamp_millis();
+
+        for prompt in prompts {
+            let stored_level: Option<String> = tx
+                .query_row(
+                    "SELECT level FROM reflection_prompts WHERE id = ?",
+                    [&prompt.id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            if let Some(stored_level) = stored_level.filter(|l| l != level) {
+                anyhow::bail!("Prompt {} belongs to {} reflections", prompt.id, stored_level);
+            }
+        }
+
+        tx.execute(
+            "UPDATE reflection_prompts SET archived_at = ? WHERE level = ? AND archived_at IS NULL",
+            params![now, level],
+        )?;
+        for (position, prompt) in prompts.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO reflection_prompts (id, level, text, position, archived_at)
+                 VALUES (?, ?, ?, ?, NULL)
+                 ON CONFLICT(id) DO UPDATE SET
+                     text = excluded.text,
+                     position = excluded.position,
+                     archived_at = NULL",
+                params![prompt.id, level, prompt.text, position as i32],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_db() -> Database {
+        let db = Database::new(":memory:".into()).unwrap();
+        db.migrate().unwrap();
+        db
+    }
+
+    fn reflection(insight: &str, at: i64) -> Reflection {
+        Reflection {
+            created_at: at,
+            updated_at: at,
+            ..Reflection::new(GoalLevel::Weekly, "2025-W49".into(), Some(insight.into()), None, None)
+        }
+    }
+
+    #[test]
+    fn test_insert_then_update_keeps_id_and_created_at() {
+        let db = test_db();
+        // Another row first, so a wrong rowid would be noticed
+        db.save_reflection(&Reflection::new(GoalLevel::Monthly, "2025-12".into(), None, None, None))
+            .unwrap();
+
+        let inserted = db.save_reflection(&reflection("first", 1_000)).unwrap();
+        assert_eq!(inserted.created_at, 1_000);
+        assert_eq!(inserted.updated_at, 1_000);
+
+        let updated = db.save_reflection(&reflection("second", 2_000)).unwrap();
+        assert_eq!(updated.id, inserted.id);
+        assert_eq!(updated.insight_1.as_deref(), Some("second"));
+        assert_eq!(updated.created_at, 1_000);
+        assert_eq!(updated.updated_at, 2_000);
+
+        let stored = db.get_reflection("weekly", "2025-W49").unwrap().unwrap();
+        assert_eq!(stored, updated);
+    }
+
+    #[test]
+    fn test_updates_keep_revisions() {
+        let db = test_db();
+        db.save_reflection(&reflection("first", 1_000)).unwrap();
+        db.save_reflection(&reflection("second", 2_000)).unwrap();
+        db.save_reflection(&reflection("third", 3_000)).unwrap();
+
+        let revisions = db.get_reflection_revisions("weekly", "2025-W49").unwrap();
+        let insights: Vec<_> = revisions.iter().map(|r| r.insight_1.as_deref()).collect();
+        assert_eq!(insights, [Some("second"), Some("first")]);
+        assert_eq!(revisions[0].revised_at, 3_000);
+    }
+
+    fn prompt(id: &str, level: GoalLevel, text: &str) -> ReflectionPrompt {
+        ReflectionPrompt { id: id.into(), level, text: text.into(), position: 0, archived_at: None }
+    }
+
+    fn prompt_texts(db: &Database, level: &str, include_archived: bool) -> Vec<String> {
+        db.get_reflection_prompts(level, include_archived).unwrap().into_iter().map(|p| p.text).collect()
+    }
+
+    #[test]
+    fn test_prompt_sets_archive_removed_prompts() {
+        let db = test_db();
+        db.set_reflection_prompts("weekly", &[
+            prompt("a", GoalLevel::Weekly, "What went well?"),
+            prompt("b", GoalLevel::Weekly, "What got in the way?"),
+        ]).unwrap();
+
+        // b は文言を変えても id が残り、a はアーカイブされて末尾に並ぶ
+        db.set_reflection_prompts("weekly", &[
+            prompt("c", GoalLevel::Weekly, "What will I change?"),
+            prompt("b", GoalLevel::Weekly, "What slowed me down?"),
+        ]).unwrap();
+        assert_eq!(prompt_texts(&db, "weekly", false), ["What will I change?", "What slowed me down?"]);
+        assert_eq!(prompt_texts(&db, "weekly", true), ["What will I change?", "What slowed me down?", "What went well?"]);
+        let archived = db.get_reflection_prompts("weekly", true).unwrap();
+        assert!(archived[2].archived_at.is_some());
+
+        // 戻したプロンプトはアーカイブを解除する
+        db.set_reflection_prompts("weekly", &[prompt("a", GoalLevel::Weekly, "What went well?")]).unwrap();
+        assert_eq!(prompt_texts(&db, "weekly", false), ["What went well?"]);
+        assert!(prompt_texts(&db, "monthly", true).is_empty());
+    }
+
+    #[test]
+    fn test_prompt_ids_of_another_level_are_rejected() {
+        let db = test_db();
+        db.set_reflection_prompts("weekly", &[prompt("a", GoalLevel::Weekly, "What went well?")]).unwrap();
+
+        let result = db.set_reflection_prompts("monthly", &[
+            prompt("m", GoalLevel::Monthly, "Biggest win?"),
+            prompt("a", GoalLevel::Monthly, "Taken over"),
+        ]);
+        assert!(result.is_err());
+        // 何も変わらない
+        assert_eq!(prompt_texts(&db, "weekly", false), ["What went well?"]);
+        assert!(prompt_texts(&db, "monthly", true).is_empty());
+    }
+
+    #[test]
+    fn test_migration_keeps_reflections_from_before_details() {
+        let db = Database::new(":memory:".into()).unwrap();
+        db.conn.lock().unwrap().execute_batch(
+            "CREATE TABLE reflections (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                level TEXT NOT NULL,
+                period_key TEXT NOT NULL,
+                insight_1 TEXT,
+                insight_2 TEXT,
+                insight_3 TEXT,
+                created_at INTEGER NOT NULL,
+                UNIQUE(level, period_key)
+            );
+            INSERT INTO reflections (level, period_key, insight_1, created_at)
+            VALUES ('weekly', '2025-W49', 'Shipped it', 1000);"
+        ).unwrap();
+
+        db.migrate().unwrap();
+        let stored = db.get_reflection("weekly", "2025-W49").unwrap().unwrap();
+        assert_eq!(stored.insight_1.as_deref(), Some("Shipped it"));
+        assert_eq!(stored.details, ReflectionDetails::default());
+        assert_eq!(stored.updated_at, 1_000);
+
+        // 詳細を保存できる
+        let mut details = ReflectionDetails { rating: Some(4), ..Default::default() };
+        details.answers.insert("a".into(), "More focus".into());
+        let saved = db.save_reflection(&Reflection {
+            created_at: 2_000,
+            updated_at: 2_000,
+            ..stored.clone().with_details(details.clone())
+        }).unwrap();
+        assert_eq!(saved.details, details);
+        assert_eq!(saved.created_at, 1_000);
+        db.migrate().unwrap();
+        assert_eq!(db.get_reflection("weekly", "2025-W49").unwrap().unwrap().details, details);
     }
 }
@@ -0,0 +1,452 @@
+use anyhow::Result;
+use rusqlite::{params_from_iter, types::Value, Connection};
+use crate::commands::periods::period_key;
+use crate::models::{GoalLevel, SearchFilters, SearchHit, SearchSource};
+use crate::db::Database;
+
+const DEFAULT_LIMIT: u32 = 50;
+const SNIPPET_CONTEXT_CHARS: usize = 24;
+
+/// Searchable text of a reflection: insights, notes and prompt answers.
+/// `row` is the column prefix, e.g. `new.` inside a trigger.
+fn reflection_text(row: &str) -> String {
+    format!(
+        "concat_ws(char(10), {row}insight_1, {row}insight_2, {row}insight_3, {row}body,
+                   (SELECT group_concat(value, char(10)) FROM json_each(COALESCE({row}answers, '{{}}'))))"
+    )
+}
+
+/// Create the FTS5 index and the triggers that keep it in sync with
+/// `goals` and `reflections`. Existing rows are indexed the first time.
+pub(crate) fn create_search_index(conn: &Connection) -> Result<()> {
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'search_index')",
+        [],
+        |row| row.get(0),
+    )?;
+
+    // trigram はスペースで区切られない日本語でも部分一致で検索できる
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+            title,
+            body,
+            source UNINDEXED,
+            source_id UNINDEXED,
+            tokenize = 'trigram'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS goals_search_insert AFTER INSERT ON goals BEGIN
+            INSERT INTO search_index (title, body, source, source_id)
+            VALUES (new.title, COALESCE(new.note, ''), 'goal', new.id);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS goals_search_update AFTER UPDATE OF title, note ON goals BEGIN
+            DELETE FROM search_index WHERE source = 'goal' AND source_id = old.id;
+            INSERT INTO search_index (title, body, source, source_id)
+            VALUES (new.title, COALESCE(new.note, ''), 'goal', new.id);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS goals_search_delete AFTER DELETE ON goals BEGIN
+            DELETE FROM search_index WHERE source = 'goal' AND source_id = old.id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS reflections_search_delete AFTER DELETE ON reflections BEGIN
+            DELETE FROM search_index WHERE source = 'reflection' AND source_id = CAST(old.id AS TEXT);
+        END;",
+    )?;
+
+    // The indexed reflection columns have grown over time, so these triggers
+    // are recreated on every start instead of only when missing
+    conn.execute_batch(&format!(
+        "DROP TRIGGER IF EXISTS reflections_search_insert;
+        DROP TRIGGER IF EXISTS reflections_search_update;
+
+        CREATE TRIGGER reflections_search_insert AFTER INSERT ON reflections BEGIN
+            INSERT INTO search_index (title, body, source, source_id)
+            VALUES ('', {text}, 'reflection', CAST(new.id AS TEXT));
+        END;
+
+        CREATE TRIGGER reflections_search_update
+        AFTER UPDATE OF insight_1, insight_2, insight_3, body, answers ON reflections BEGIN
+            DELETE FROM search_index WHERE source = 'reflection' AND source_id = CAST(old.id AS TEXT);
+            INSERT INTO search_index (title, body, source, source_id)
+            VALUES ('', {text}, 'reflection', CAST(new.id AS TEXT));
+        END;",
+        text = reflection_text("new.")
+    ))?;
+
+    if !exists {
+        conn.execute_batch(&format!(
+            "INSERT INTO search_index (title, body, source, source_id)
+             SELECT title, COALESCE(note, ''), 'goal', id FROM goals;
+             INSERT INTO search_index (title, body, source, source_id)
+             SELECT '', {}, 'reflection', CAST(id AS TEXT)
+             FROM reflections;",
+            reflection_text("")
+        ))?;
+    }
+
+    Ok(())
+}
+
+impl Database {
+    /// Full-text search over goal titles/notes and reflection insights,
+    /// best matches first.
+    pub fn search(&self, query: &str, filters: &SearchFilters, week_start: i32) -> Result<Vec<SearchHit>> {
+        let terms: Vec<&str> = query.split_whitespace().collect();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // The trigram tokenizer cannot match terms shorter than three characters,
+        // so those queries fall back to a substring scan of the index
+        let use_fts = terms.iter().all(|t| t.chars().count() >= 3);
+
+        let mut sql = if use_fts {
+            String::from(
+                "SELECT s.source, s.source_id,
+                        snippet(search_index, -1, '<mark>', '</mark>', '…', 32),
+                        bm25(search_index, 2.0, 1.0),
+                        s.title, s.body,
+                        g.level, g.period_start, g.is_completed, r.level, r.period_key
+                 FROM search_index s",
+            )
+        } else {
+            String::from(
+                "SELECT s.source, s.source_id, '', 0.0, s.title, s.body,
+                        g.level, g.period_start, g.is_completed, r.level, r.period_key
+                 FROM search_index s",
+            )
+        };
+        sql.push_str(
+            "
+             LEFT JOIN goals g ON s.source = 'goal' AND g.id = s.source_id AND g.deleted_at IS NULL
+             LEFT JOIN reflections r ON s.source = 'reflection' AND CAST(r.id AS TEXT) = s.source_id
+             WHERE COALESCE(g.level, r.level) IS NOT NULL",
+        );
+
+        let mut params: Vec<Value> = Vec::new();
+
+        if use_fts {
+            let fts_query = terms
+                .iter()
+                .map(|t| format!("\"{}\"", t.replace('"', "\"\"")))
+                .collect::<Vec<_>>()
+                .join(" ");
+            sql.push_str(" AND search_index MATCH ?");
+            params.push(Value::Text(fts_query));
+        } else {
+            for term in &terms {
+                let pattern = format!("%{}%", escape_like(term));
+                sql.push_str(" AND (s.title LIKE ? ESCAPE '\\' OR s.body LIKE ? ESCAPE '\\')");
+                params.push(Value::Text(pattern.clone()));
+                params.push(Value::Text(pattern));
+            }
+        }
+
+        if let Some(level) = filters.level {
+            sql.push_str(" AND COALESCE(g.level, r.level) = ?");
+            params.push(Value::Text(level.as_str().to_string()));
+        }
+
+        if let Some(source) = filters.source {
+            sql.push_str(" AND s.source = ?");
+            params.push(Value::Text(source.as_str().to_string()));
+        }
+
+        // Goals are compared by period start, reflections by their period key
+        // (keys of the same level sort chronologically)
+        for (bound, op) in [(filters.from, ">="), (filters.to, "<")] {
+            let Some(bound) = bound else { continue };
+            let keys: Vec<String> = [GoalLevel::Daily, GoalLevel::Weekly, GoalLevel::Monthly]
+                .iter()
+                .filter_map(|level| period_key(*level, bound, week_start))
+                .collect();
+            if keys.len() != 3 {
+                anyhow::bail!("Invalid date filter: {}", bound);
+            }
+            sql.push_str(&format!(
+                " AND (g.period_start {op} ? OR r.period_key {op}
+                       CASE r.level WHEN 'daily' THEN ? WHEN 'weekly' THEN ? ELSE ? END)"
+            ));
+            params.push(Value::Integer(bound));
+            params.extend(keys.into_iter().map(Value::Text));
+        }
+
+        sql.push_str(" ORDER BY 4, g.period_start DESC, r.period_key DESC LIMIT ?");
+        params.push(Value::Integer(filters.limit.unwrap_or(DEFAULT_LIMIT) as i64));
+
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(params), |row| {
+            Ok(SearchRow {
+                source: row.get(0)?,
+                source_id: row.get(1)?,
+                snippet: row.get(2)?,
+                rank: row.get(3)?,
+                title: row.get(4)?,
+                body: row.get(5)?,
+                goal_level: row.get(6)?,
+                goal_period_start: row.get(7)?,
+                goal_completed: row.get::<_, Option<i32>>(8)?.map(|v| v != 0),
+                reflection_level: row.get(9)?,
+                reflection_period_key: row.get(10)?,
+            })
+        })?;
+
+        let mut hits = Vec::new();
+        for row in rows {
+            let row = row?;
+            let Some(source) = SearchSource::from_str(&row.source) else { continue };
+            let level = row
+                .goal_level
+                .as_deref()
+                .or(row.reflection_level.as_deref())
+                .and_then(GoalLevel::from_str)
+                .unwrap_or(GoalLevel::Daily);
+            let period_key = match source {
+                SearchSource::Goal => row
+                    .goal_period_start
+                    .and_then(|ts| period_key(level, ts, week_start))
+                    .unwrap_or_default(),
+                SearchSource::Reflection => row.reflection_period_key.unwrap_or_default(),
+            };
+            let snippet = if use_fts {
+                row.snippet
+            } else if terms.iter().all(|t| contains_ignore_ascii_case(&row.title, t)) {
+                highlight(&row.title, &terms)
+            } else {
+                highlight(&row.body, &terms)
+            };
+
+            hits.push(SearchHit {
+                source,
+                id: row.source_id,
+                level,
+                period_key,
+                title: row.title,
+                snippet,
+                is_completed: row.goal_completed,
+                rank: row.rank,
+            });
+        }
+
+        Ok(hits)
+    }
+}
+
+struct SearchRow {
+    source: String,
+    source_id: String,
+    snippet: String,
+    rank: f64,
+    title: String,
+    body: String,
+    goal_level: Option<String>,
+    goal_period_start: Option<i64>,
+    goal_completed: Option<bool>,
+    reflection_level: Option<String>,
+    reflection_period_key: Option<String>,
+}
+
+fn escape_like(term: &str) -> String {
+    term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+fn find_ignore_ascii_case(haystack: &str, needle: &str, from: usize) -> Option<usize> {
+    haystack[from..]
+        .char_indices()
+        .map(|(i, _)| from + i)
+        .find(|&i| {
+            haystack.len() - i >= needle.len()
+                && haystack.is_char_boundary(i + needle.len())
+                && haystack[i..i + needle.len()].eq_ignore_ascii_case(needle)
+        })
+}
+
+fn contains_ignore_ascii_case(haystack: &str, needle: &str) -> bool {
+    find_ignore_ascii_case(haystack, needle, 0).is_some()
+}
+
+/// Build a snippet around the first hit and wrap every hit in `<mark>` tags,
+/// mirroring what FTS5's `snippet()` returns for longer queries
+fn highlight(text: &str, terms: &[&str]) -> String {
+    let first = terms
+        .iter()
+        .filter_map(|t| find_ignore_ascii_case(text, t, 0))
+        .min()
+        .unwrap_or(0);
+    let start = text[..first]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_CONTEXT_CHARS - 1)
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    let end = text[first..]
+        .char_indices()
+        .nth(SNIPPET_CONTEXT_CHARS * 2)
+        .map(|(i, _)| first + i)
+        .unwrap_or(text.len());
+    let window = &text[start..end];
+
+    let mut result = String::new();
+    if start > 0 {
+        result.push('…');
+    }
+    let mut pos = 0;
+    while pos < window.len() {
+        let hit = terms
+            .iter()
+            .filter_map(|t| find_ignore_ascii_case(window, t, pos).map(|i| (i, t.len())))
+            .min();
+        match hit {
+            Some((i, len)) => {
+                result.push_str(&window[pos..i]);
+                result.push_str("<mark>");
+                result.push_str(&window[i..i + len]);
+                result.push_str("</mark>");
+                pos = i + len;
+            }
+            None => {
+                result.push_str(&window[pos..]);
+                break;
+            }
+        }
+    }
+    if end < text.len() {
+        result.push('…');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use crate::commands::periods::local_midnight;
+    use crate::models::{Goal, Reflection};
+
+    fn test_db() -> Database {
+        let db = Database::new(":memory:".into()).unwrap();
+        db.migrate().unwrap();
+        db
+    }
+
+    fn day(d: u32) -> i64 {
+        local_midnight(NaiveDate::from_ymd_opt(2026, 10, d).unwrap()).timestamp_millis()
+    }
+
+    fn add_goal(db: &Database, title: &str, level: GoalLevel, period_start: i64) -> Goal {
+        let goal = Goal::new(title.to_string(), level, period_start, None);
+        db.add_goal(&goal).unwrap();
+        goal
+    }
+
+    fn titles(hits: &[SearchHit]) -> Vec<&str> {
+        hits.iter().map(|hit| hit.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_search_follows_goal_and_reflection_changes() {
+        let db = test_db();
+        let filters = SearchFilters::default();
+        let goal = add_goal(&db, "Write quarterly report", GoalLevel::Weekly, day(12));
+
+        let hits = db.search("quarterly", &filters, 2).unwrap();
+        assert_eq!(titles(&hits), ["Write quarterly report"]);
+        assert_eq!(hits[0].id, goal.id);
+        assert_eq!(hits[0].period_key, "2026-W42");
+        assert!(hits[0].snippet.contains("<mark>quarterly</mark>"));
+
+        // タイトル変更・ゴミ箱・完全削除にトリガーが追従する
+        db.update_goal(&goal.id, "Write annual report").unwrap();
+        assert!(db.search("quarterly", &filters, 2).unwrap().is_empty());
+        assert_eq!(db.search("annual", &filters, 2).unwrap().len(), 1);
+        db.delete_goal(&goal.id).unwrap();
+        assert!(db.search("annual", &filters, 2).unwrap().is_empty());
+        db.restore_goal(&goal.id).unwrap();
+        assert_eq!(db.search("annual", &filters, 2).unwrap().len(), 1);
+        db.remove_goal(&goal.id).unwrap();
+        let count: i64 = db.conn.lock().unwrap()
+            .query_row("SELECT COUNT(*) FROM search_index WHERE source = 'goal'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+
+        let reflection = Reflection::new(GoalLevel::Weekly, "2026-W42".into(), Some("Found a steady rhythm".into()), None, None);
+        db.save_reflection(&reflection).unwrap();
+        let hits = db.search("rhythm", &filters, 2).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].source, SearchSource::Reflection);
+        assert_eq!(hits[0].period_key, "2026-W42");
+
+        let edited = Reflection::new(GoalLevel::Weekly, "2026-W42".into(), Some("Slept better".into()), None, None);
+        db.save_reflection(&edited).unwrap();
+        assert!(db.search("rhythm", &filters, 2).unwrap().is_empty());
+        assert_eq!(db.search("slept", &filters, 2).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_short_queries_use_substring_scan() {
+        let db = test_db();
+        let filters = SearchFilters::default();
+        add_goal(&db, "銀行に電話", GoalLevel::Daily, day(18));
+        add_goal(&db, "Ship 100% of the UI", GoalLevel::Daily, day(18));
+        add_goal(&db, "Ship 1000 units", GoalLevel::Daily, day(18));
+
+        // trigram では拾えない 2 文字の検索
+        let hits = db.search("銀行", &filters, 2).unwrap();
+        assert_eq!(titles(&hits), ["銀行に電話"]);
+        assert_eq!(hits[0].snippet, "<mark>銀行</mark>に電話");
+
+        // LIKE の % と _ は文字として扱う
+        assert_eq!(titles(&db.search("0%", &filters, 2).unwrap()), ["Ship 100% of the UI"]);
+        let hits = db.search("ui", &filters, 2).unwrap();
+        assert_eq!(hits[0].snippet, "Ship 100% of the <mark>UI</mark>");
+        assert!(db.search("  ", &filters, 2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_filters() {
+        let db = test_db();
+        add_goal(&db, "Review budget daily", GoalLevel::Daily, day(5));
+        add_goal(&db, "Review budget weekly", GoalLevel::Weekly, day(12));
+        add_goal(&db, "Review budget monthly", GoalLevel::Monthly, day(1));
+        db.save_reflection(&Reflection::new(GoalLevel::Daily, "2026-10-20".into(), Some("Review budget again".into()), None, None))
+            .unwrap();
+
+        let weekly = SearchFilters { level: Some(GoalLevel::Weekly), ..Default::default() };
+        assert_eq!(titles(&db.search("budget", &weekly, 2).unwrap()), ["Review budget weekly"]);
+
+        let reflections = SearchFilters { source: Some(SearchSource::Reflection), ..Default::default() };
+        let hits = db.search("budget", &reflections, 2).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].period_key, "2026-10-20");
+
+        // 目標は period_start、振り返りは期間キーで比較する
+        let range = SearchFilters { from: Some(day(5)), to: Some(day(13)), ..Default::default() };
+        let mut found = titles(&db.search("budget", &range, 2).unwrap()).into_iter().map(String::from).collect::<Vec<_>>();
+        found.sort();
+        assert_eq!(found, ["Review budget daily", "Review budget weekly"]);
+        let later = SearchFilters { from: Some(day(19)), ..Default::default() };
+        let hits = db.search("budget", &later, 2).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].source, SearchSource::Reflection);
+
+        let limited = SearchFilters { limit: Some(2), ..Default::default() };
+        assert_eq!(db.search("budget", &limited, 2).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_highlight() {
+        assert_eq!(highlight("Call the bank", &["BANK"]), "Call the <mark>bank</mark>");
+        assert_eq!(highlight("ab ab", &["ab"]), "<mark>ab</mark> <mark>ab</mark>");
+
+        // 前後 SNIPPET_CONTEXT_CHARS 文字ずつに切り詰め、マルチバイトでも文字境界で切る
+        let text = format!("{}目標{}", "あ".repeat(40), "い".repeat(60));
+        let snippet = highlight(&text, &["目標"]);
+        assert_eq!(
+            snippet,
+            format!("…{}<mark>目標</mark>{}…", "あ".repeat(24), "い".repeat(46)),
+        );
+    }
+}
@@ -1,8 +1,45 @@
 use anyhow::Result;
-use rusqlite::params;
+use rusqlite::{params, Connection, OptionalExtension};
 use std::collections::HashMap;
+use crate::commands::periods::week_key_from_iso;
 use crate::db::Database;
 
+/// Rewrite the week the weekly reflection prompt was last shown for once.
+/// It held an ISO week until week keys were made to match the frontend's
+/// numbering. `week_key_scheme` marks it as converted so keys written
+/// afterwards are left alone.
+pub(crate) fn migrate_weekly_prompt_key(conn: &Connection) -> Result<()> {
+    let scheme: Option<String> = conn
+        .query_row("SELECT value FROM settings WHERE key = 'week_key_scheme'", [], |row| row.get(0))
+        .optional()?;
+    if scheme.is_some() {
+        return Ok(());
+    }
+
+    let week_start: i32 = conn
+        .query_row("SELECT value FROM settings WHERE key = 'week_start'", [], |row| row.get::<_, String>(0))
+        .optional()?
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(2);
+    let value: Option<String> = conn
+        .query_row("SELECT value FROM settings WHERE key = 'last_weekly_reflection_prompt'", [], |row| row.get(0))
+        .optional()?;
+    if let Some(value) = value.filter(|value| !value.is_empty()) {
+        // 読めない値は空に戻す（次の週から通常どおり動く）
+        let converted = week_key_from_iso(&value, week_start).unwrap_or_default();
+        conn.execute(
+            "UPDATE settings SET value = ? WHERE key = 'last_weekly_reflection_prompt'",
+            params![converted],
+        )?;
+    }
+
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES ('week_key_scheme', 'periods')",
+        [],
+    )?;
+    Ok(())
+}
+
 impl Database {
     pub fn get_setting(&self, key: &str) -> Result<String> {
         let conn = self.conn.lock().unwrap();
@@ -35,3 +72,32 @@ impl Database {
         Ok(map)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_weekly_prompt_key() {
+        let db = Database::new(":memory:".into()).unwrap();
+        db.migrate().unwrap();
+        assert_eq!(db.get_setting("week_key_scheme").unwrap(), "periods");
+
+        // 変換前のデータベースを再現する
+        {
+            let conn = db.conn.lock().unwrap();
+            conn.execute("DELETE FROM settings WHERE key = 'week_key_scheme'", []).unwrap();
+        }
+        db.set_setting("week_start", "2").unwrap();
+        db.set_setting("last_weekly_reflection_prompt", "2026-W01").unwrap();
+        db.migrate().unwrap();
+
+        // ISO 2026-W01 starts on Monday 2025-12-29
+        assert_eq!(db.get_setting("last_weekly_reflection_prompt").unwrap(), "2025-W53");
+
+        // Keys written after the conversion are kept as they are
+        db.set_setting("last_weekly_reflection_prompt", "2026-W01").unwrap();
+        db.migrate().unwrap();
+        assert_eq!(db.get_setting("last_weekly_reflection_prompt").unwrap(), "2026-W01");
+    }
+}
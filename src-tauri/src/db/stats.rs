@@ -0,0 +1,253 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use rusqlite::params;
+use crate::models::GoalLevel;
+use crate::db::Database;
+
+/// Goals of one period, as grouped by SQLite in local time
+#[derive(Debug, Clone)]
+pub struct PeriodSummary {
+    pub period_start: NaiveDate,
+    pub total: u32,
+    pub completed: u32,
+}
+
+/// SQL expression mapping `period_start` to the local date its period begins on
+fn period_start_date_sql(level: GoalLevel, week_start: i32) -> String {
+    match level {
+        GoalLevel::Daily => "date(period_start / 1000, 'unixepoch', 'localtime')".to_string(),
+        // 'weekday N' moves forward to the next day with weekday N (0 = Sunday),
+        // so stepping back six days first lands on the start of the week
+        GoalLevel::Weekly => format!(
+            "date(period_start / 1000, 'unixepoch', 'localtime', '-6 days', 'weekday {}')",
+            (week_start - 1).rem_euclid(7)
+        ),
+        GoalLevel::Monthly => {
+            "date(period_start / 1000, 'unixepoch', 'localtime', 'start of month')".to_string()
+        }
+    }
+}
+
+impl Database {
+    /// Total and completed goal counts per level for periods starting in `[from, to)`
+    pub fn completion_counts(&self, from: i64, to: i64) -> Result<Vec<(GoalLevel, u32, u32)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT level, COUNT(*), COALESCE(SUM(is_completed), 0)
+             FROM goals
+             WHERE deleted_at IS NULL AND period_start >= ? AND period_start < ?
+             GROUP BY level"
+        )?;
+
+        let rows = stmt.query_map(params![from, to], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?, row.get::<_, u32>(2)?))
+        })?;
+
+        let mut counts = Vec::new();
+        for row in rows {
+            let (level, total, completed) = row?;
+            if let Some(level) = GoalLevel::from_str(&level) {
+                counts.push((level, total, completed));
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Average `completed_at - created_at` (ms) and sample size per level
+    /// for completed goals whose period starts in `[from, to)`
+    pub fn average_completion_times(&self, from: i64, to: i64) -> Result<Vec<(GoalLevel, i64, u32)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT level, CAST(AVG(completed_at - created_at) AS INTEGER), COUNT(*)
+             FROM goals
+             WHERE deleted_at IS NULL AND is_completed = 1 AND completed_at IS NOT NULL
+               AND period_start >= ? AND period_start < ?
+             GROUP BY level"
+        )?;
+
+        let rows = stmt.query_map(params![from, to], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, u32>(2)?))
+        })?;
+
+        let mut averages = Vec::new();
+        for row in rows {
+            let (level, average, count) = row?;
+            if let Some(level) = GoalLevel::from_str(&level) {
+                averages.push((level, average, count));
+            }
+        }
+        Ok(averages)
+    }
+
+    /// Completions in `[from, to)` bucketed by a local-time `strftime` field
+    /// (`%w` for weekday, `%H` for hour)
+    fn completions_by(&self, field: &str, buckets: usize, from: i64, to: i64) -> Result<Vec<u32>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT CAST(strftime('{field}', completed_at / 1000, 'unixepoch', 'localtime') AS INTEGER), COUNT(*)
+             FROM goals
+             WHERE deleted_at IS NULL AND is_completed = 1
+               AND completed_at >= ? AND completed_at < ?
+             GROUP BY 1"
+        ))?;
+
+        let rows = stmt.query_map(params![from, to], |row| {
+            Ok((row.get::<_, usize>(0)?, row.get::<_, u32>(1)?))
+        })?;
+
+        let mut counts = vec![0; buckets];
+        for row in rows {
+            let (bucket, count) = row?;
+            if let Some(slot) = counts.get_mut(bucket) {
+                *slot = count;
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Completions per weekday (0 = Sunday) with `completed_at` in `[from, to)`
+    pub fn completions_by_weekday(&self, from: i64, to: i64) -> Result<Vec<u32>> {
+        self.completions_by("%w", 7, from, to)
+    }
+
+    /// Completions per local hour with `completed_at` in `[from, to)`
+    pub fn completions_by_hour(&self, from: i64, to: i64) -> Result<Vec<u32>> {
+        self.completions_by("%H", 24, from, to)
+    }
+
+    /// Goal counts of every period of `level` that has goals, oldest first
+    pub fn period_summaries(&self, level: GoalLevel, week_start: i32) -> Result<Vec<PeriodSummary>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} AS period_date, COUNT(*), COALESCE(SUM(is_completed), 0)
+             FROM goals
+             WHERE deleted_at IS NULL AND level = ?
+             GROUP BY period_date
+             ORDER BY period_date ASC",
+            period_start_date_sql(level, week_start)
+        ))?;
+
+        let rows = stmt.query_map([level.as_str()], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?, row.get::<_, u32>(2)?))
+        })?;
+
+        let mut summaries = Vec::new();
+        for row in rows {
+            let (date, total, completed) = row?;
+            if let Ok(period_start) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+                summaries.push(PeriodSummary { period_start, total, completed });
+            }
+        }
+        Ok(summaries)
+    }
+
+    /// Number of goal events of `event_type` that occurred in `[from, to)`
+    pub fn count_goal_events(&self, event_type: &str, from: i64, to: i64) -> Result<u32> {
+        let conn = self.conn.lock().unwrap();
+        let count = conn.query_row(
+            "SELECT COUNT(*) FROM goal_events
+             WHERE event_type = ? AND occurred_at >= ? AND occurred_at < ?",
+            params![event_type, from, to],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use crate::commands::periods::local_midnight;
+    use crate::models::Goal;
+
+    fn test_db() -> Database {
+        let db = Database::new(":memory:".into()).unwrap();
+        db.migrate().unwrap();
+        db
+    }
+
+    fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    /// Noon of `date` in local time (ms)
+    fn noon(date: NaiveDate) -> i64 {
+        (local_midnight(date) + Duration::hours(12)).timestamp_millis()
+    }
+
+    /// Add a goal of `date`, completed at `completed_at` and/or trashed
+    fn add(db: &Database, level: GoalLevel, date: NaiveDate, completed_at: Option<i64>, trashed: bool) {
+        let goal = Goal {
+            is_completed: completed_at.is_some(),
+            completed_at,
+            created_at: noon(date),
+            deleted_at: trashed.then_some(noon(date)),
+            ..Goal::new("goal".to_string(), level, noon(date), None)
+        };
+        db.write_goal_snapshot(&goal).unwrap();
+    }
+
+    #[test]
+    fn test_completion_counts() {
+        let db = test_db();
+        // 2025-12-08 は月曜日
+        add(&db, GoalLevel::Daily, day(2025, 12, 8), Some(noon(day(2025, 12, 8))), false);
+        add(&db, GoalLevel::Daily, day(2025, 12, 9), None, false);
+        add(&db, GoalLevel::Daily, day(2025, 12, 9), Some(noon(day(2025, 12, 9))), true);
+        add(&db, GoalLevel::Weekly, day(2025, 12, 8), Some(noon(day(2025, 12, 10))), false);
+        // 範囲外
+        add(&db, GoalLevel::Daily, day(2025, 12, 15), None, false);
+
+        let from = local_midnight(day(2025, 12, 8)).timestamp_millis();
+        let to = local_midnight(day(2025, 12, 15)).timestamp_millis();
+        let mut counts = db.completion_counts(from, to).unwrap();
+        counts.sort_by_key(|(level, _, _)| level.as_str().to_string());
+        assert_eq!(counts, [(GoalLevel::Daily, 2, 1), (GoalLevel::Weekly, 1, 1)]);
+
+        // 作成から完了まで: 日次は当日中、週次は2日
+        let mut averages = db.average_completion_times(from, to).unwrap();
+        averages.sort_by_key(|(level, _, _)| level.as_str().to_string());
+        let two_days = noon(day(2025, 12, 10)) - noon(day(2025, 12, 8));
+        assert_eq!(averages, [(GoalLevel::Daily, 0, 1), (GoalLevel::Weekly, two_days, 1)]);
+
+        // ゴミ箱の目標の完了は数えない
+        let weekdays = db.completions_by_weekday(from, to).unwrap();
+        assert_eq!((weekdays[1], weekdays[2], weekdays[3]), (1, 0, 1));
+        assert_eq!(db.completions_by_hour(from, to).unwrap()[12], 2);
+    }
+
+    #[test]
+    fn test_period_summaries() {
+        let db = test_db();
+        add(&db, GoalLevel::Daily, day(2025, 12, 8), Some(noon(day(2025, 12, 8))), false);
+        add(&db, GoalLevel::Daily, day(2025, 12, 8), None, false);
+        add(&db, GoalLevel::Daily, day(2025, 12, 8), None, true);
+        add(&db, GoalLevel::Daily, day(2025, 12, 10), None, true);
+        // 日曜は月曜始まりなら前の週、日曜始まりなら次の週
+        add(&db, GoalLevel::Weekly, day(2025, 12, 14), Some(noon(day(2025, 12, 14))), false);
+        add(&db, GoalLevel::Weekly, day(2025, 12, 10), None, false);
+        add(&db, GoalLevel::Monthly, day(2025, 11, 30), None, false);
+        add(&db, GoalLevel::Monthly, day(2025, 12, 1), None, false);
+
+        let summarize = |level, week_start| -> Vec<(NaiveDate, u32, u32)> {
+            db.period_summaries(level, week_start)
+                .unwrap()
+                .into_iter()
+                .map(|s| (s.period_start, s.total, s.completed))
+                .collect()
+        };
+
+        // ゴミ箱だけの日は期間として出てこない
+        assert_eq!(summarize(GoalLevel::Daily, 2), [(day(2025, 12, 8), 2, 1)]);
+        assert_eq!(summarize(GoalLevel::Weekly, 2), [(day(2025, 12, 8), 2, 1)]);
+        assert_eq!(
+            summarize(GoalLevel::Weekly, 1),
+            [(day(2025, 12, 7), 1, 0), (day(2025, 12, 14), 1, 1)],
+        );
+        assert_eq!(
+            summarize(GoalLevel::Monthly, 2),
+            [(day(2025, 11, 1), 1, 0), (day(2025, 12, 1), 1, 0)],
+        );
+    }
+}
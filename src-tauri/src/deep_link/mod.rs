@@ -0,0 +1,298 @@
+// trivyn:// links, from wikis, launchers and exported reports. macOS delivers
+// them as open-url events; Windows and Linux start the app with the link as
+// its argument, which `cli` parses.
+
+use std::sync::Mutex;
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime, Url};
+use crate::cli::QuickAdd;
+use crate::commands::goals::get_week_start_setting;
+use crate::commands::periods::{local_midnight, period_key_start, period_range};
+use crate::db::Database;
+use crate::models::{Goal, GoalLevel};
+
+pub const SCHEME: &str = "trivyn";
+
+/// What a `trivyn://` link asks for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLink {
+    /// trivyn://add?level=daily&title=Write%20RFC (level defaults to daily),
+    /// filled into the quick-add window for the user to confirm
+    Add(QuickAdd),
+    /// trivyn://toggle/<goal-id> shows the goal and asks before checking it
+    /// off (or back on)
+    Toggle { goal_id: String },
+    /// trivyn://reflect/weekly/2026-W42, or trivyn://reflect/weekly for the
+    /// period that just ended
+    Reflect { level: GoalLevel, period_key: Option<String> },
+    /// trivyn://history?date=2026-10-01 (today when omitted)
+    History { date: Option<NaiveDate> },
+}
+
+impl DeepLink {
+    pub fn parse(link: &str) -> Result<Self, String> {
+        let url = Url::parse(link).map_err(|e| format!("Invalid link {}: {}", link, e))?;
+        if url.scheme() != SCHEME {
+            return Err(format!("Not a {}:// link: {}", SCHEME, link));
+        }
+
+        let segments: Vec<String> = url
+            .path_segments()
+            .map(|segments| segments.filter(|s| !s.is_empty()).map(percent_decode).collect())
+            .unwrap_or_default();
+        let query = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+        let parse_level = |value: &str| {
+            GoalLevel::from_str(value).ok_or_else(|| format!("Invalid level in {}: {}", link, value))
+        };
+
+        match (url.host_str().unwrap_or(""), segments.as_slice()) {
+            ("add", []) => {
+                let title = query("title").unwrap_or_default().trim().to_string();
+                if title.is_empty() {
+                    return Err(format!("Missing title in {}", link));
+                }
+                let level = match query("level") {
+                    Some(level) => parse_level(&level)?,
+                    None => GoalLevel::Daily,
+                };
+                Ok(DeepLink::Add(QuickAdd { title, level }))
+            }
+            ("toggle", [goal_id]) => Ok(DeepLink::Toggle { goal_id: goal_id.clone() }),
+            ("reflect", [level]) => Ok(DeepLink::Reflect { level: parse_level(level)?, period_key: None }),
+            ("reflect", [level, key]) => Ok(DeepLink::Reflect {
+                level: parse_level(level)?,
+                period_key: Some(key.clone()),
+            }),
+            ("history", []) => {
+                let date = query("date")
+                    .map(|date| {
+                        NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                            .map_err(|_| format!("Invalid date in {}: {}", link, date))
+                    })
+                    .transpose()?;
+                Ok(DeepLink::History { date })
+            }
+            _ => Err(format!("Unknown link: {}", link)),
+        }
+    }
+}
+
+/// Decode %XX escapes in a path segment (query values are decoded by `Url`)
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// View the popover is asked to open from outside: a link or a launch argument
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "view", rename_all = "camelCase")]
+pub enum PopoverRoute {
+    /// The goals tab on `level`
+    Goals { level: GoalLevel },
+    /// The reflection of the `level` period starting at `period_start` (ms)
+    #[serde(rename_all = "camelCase")]
+    Reflection { level: GoalLevel, period_start: i64 },
+    /// The history calendar with the day starting at `date` (ms) selected
+    History { date: i64 },
+    /// `shown_in`, with a prompt asking whether to toggle `goal`
+    #[serde(rename_all = "camelCase")]
+    Toggle { goal: Goal, shown_in: Box<PopoverRoute> },
+}
+
+/// Route kept until the popover takes it, since a link can start the app
+/// before the popover has loaded
+#[derive(Default)]
+pub struct PendingRoute(Mutex<Option<PopoverRoute>>);
+
+impl PendingRoute {
+    pub fn take(&self) -> Option<PopoverRoute> {
+        self.0.lock().unwrap().take()
+    }
+}
+
+/// Show the popover and ask it to open `route`
+pub fn open_popover_at<R: Runtime>(app: &AppHandle<R>, route: PopoverRoute) -> Result<(), String> {
+    *app.state::<PendingRoute>().0.lock().unwrap() = Some(route);
+
+    if let Some(window) = app.get_webview_window("popover") {
+        window.show().map_err(|e| e.to_string())?;
+        window.set_focus().map_err(|e| e.to_string())?;
+    }
+    app.emit("popover-route-requested", ())
+        .map_err(|e| format!("Failed to emit event: {}", e))
+}
+
+/// Start (ms) of the reflection period a link points at. Without a key this
+/// is the period that ended most recently, as in reflection reminders.
+pub fn reflection_period_start(
+    level: GoalLevel,
+    period_key: Option<&str>,
+    week_start: i32,
+) -> Result<i64, String> {
+    match period_key {
+        Some(key) => period_key_start(level, key, week_start)
+            .map(|date| local_midnight(date).timestamp_millis())
+            .ok_or_else(|| format!("Invalid {} period: {}", level.as_str(), key)),
+        None => {
+            let (current_start, _) = period_range(level, &Local::now(), week_start);
+            let (previous_start, _) =
+                period_range(level, &(current_start - Duration::milliseconds(1)), week_start);
+            Ok(previous_start.timestamp_millis())
+        }
+    }
+}
+
+/// Where a goal is shown: the goals tab while its period is current,
+/// otherwise the history calendar on the first day of its period
+pub fn goal_route(goal: &Goal, now: &DateTime<Local>, week_start: i32) -> PopoverRoute {
+    let (start, end) = period_range(goal.level, now, week_start);
+    if (start.timestamp_millis()..end.timestamp_millis()).contains(&goal.period_start) {
+        return PopoverRoute::Goals { level: goal.level };
+    }
+    let date = DateTime::from_timestamp_millis(goal.period_start)
+        .map(|dt| dt.with_timezone(&Local).date_naive())
+        .unwrap_or_else(|| now.date_naive());
+    PopoverRoute::History { date: local_midnight(date).timestamp_millis() }
+}
+
+/// Carry out a link in the running app. Links never change goals by
+/// themselves, since any page can contain one: the user confirms the goal
+/// an add link fills in and the prompt a toggle link shows.
+pub fn open<R: Runtime>(app: &AppHandle<R>, link: &DeepLink) -> Result<(), String> {
+    match link {
+        DeepLink::Add(add) => {
+            crate::quick_add::show_with_goal(app, add.clone());
+            Ok(())
+        }
+        DeepLink::Toggle { goal_id } => {
+            let db = app.state::<Database>();
+            let goal = db
+                .get_goal(goal_id)
+                .map_err(|e| format!("Failed to get goal: {}", e))?
+                .filter(|goal| goal.deleted_at.is_none())
+                .ok_or_else(|| format!("Goal not found: {}", goal_id))?;
+            let shown_in = goal_route(&goal, &Local::now(), get_week_start_setting(&db));
+            open_popover_at(app, PopoverRoute::Toggle { goal, shown_in: Box::new(shown_in) })
+        }
+        DeepLink::Reflect { level, period_key } => {
+            let week_start = get_week_start_setting(&app.state::<Database>());
+            let period_start = reflection_period_start(*level, period_key.as_deref(), week_start)?;
+            open_popover_at(app, PopoverRoute::Reflection { level: *level, period_start })
+        }
+        DeepLink::History { date } => {
+            let date = date.unwrap_or_else(|| Local::now().date_naive());
+            open_popover_at(app, PopoverRoute::History { date: local_midnight(date).timestamp_millis() })
+        }
+    }
+}
+
+/// Open links delivered by the deep-link plugin (macOS)
+pub fn open_urls<R: Runtime>(app: &AppHandle<R>, urls: Vec<Url>) {
+    for url in urls {
+        let result = DeepLink::parse(url.as_str()).and_then(|link| open(app, &link));
+        if let Err(e) = result {
+            eprintln!("[DeepLink] {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_links() {
+        assert_eq!(
+            DeepLink::parse("trivyn://add?level=weekly&title=Write%20RFC").unwrap(),
+            DeepLink::Add(QuickAdd { title: "Write RFC".to_string(), level: GoalLevel::Weekly }),
+        );
+        assert_eq!(
+            DeepLink::parse("trivyn://add?title=%E9%8A%80%E8%A1%8C%E3%81%AB%E9%9B%BB%E8%A9%B1").unwrap(),
+            DeepLink::Add(QuickAdd { title: "銀行に電話".to_string(), level: GoalLevel::Daily }),
+        );
+        assert_eq!(
+            DeepLink::parse("trivyn://add?title=Fix%20%23123").unwrap(),
+            DeepLink::Add(QuickAdd { title: "Fix #123".to_string(), level: GoalLevel::Daily }),
+        );
+        assert_eq!(
+            DeepLink::parse("trivyn://toggle/6f1c2a4e-1b2d-4c3e-9f00-123456789abc").unwrap(),
+            DeepLink::Toggle { goal_id: "6f1c2a4e-1b2d-4c3e-9f00-123456789abc".to_string() },
+        );
+        assert_eq!(
+            DeepLink::parse("trivyn://reflect/weekly/2026-W42").unwrap(),
+            DeepLink::Reflect { level: GoalLevel::Weekly, period_key: Some("2026-W42".to_string()) },
+        );
+        assert_eq!(
+            DeepLink::parse("trivyn://reflect/monthly").unwrap(),
+            DeepLink::Reflect { level: GoalLevel::Monthly, period_key: None },
+        );
+        assert_eq!(
+            DeepLink::parse("trivyn://history?date=2026-10-01").unwrap(),
+            DeepLink::History { date: NaiveDate::from_ymd_opt(2026, 10, 1) },
+        );
+        assert_eq!(DeepLink::parse("trivyn://history").unwrap(), DeepLink::History { date: None });
+    }
+
+    #[test]
+    fn test_parse_invalid_links() {
+        assert!(DeepLink::parse("https://example.com/add?title=x").is_err());
+        assert!(DeepLink::parse("trivyn://add?level=daily").is_err());
+        assert!(DeepLink::parse("trivyn://add?title=x&level=yearly").is_err());
+        assert!(DeepLink::parse("trivyn://toggle").is_err());
+        assert!(DeepLink::parse("trivyn://history?date=yesterday").is_err());
+        assert!(DeepLink::parse("trivyn://delete/abc").is_err());
+    }
+
+    #[test]
+    fn test_goal_route() {
+        let now = Local::now();
+        let today = local_midnight(now.date_naive());
+        let goal = Goal::new("x".to_string(), GoalLevel::Weekly, now.timestamp_millis(), None);
+        assert_eq!(goal_route(&goal, &now, 2), PopoverRoute::Goals { level: GoalLevel::Weekly });
+
+        // 過去の目標は履歴でその期間の初日を開く
+        let start = today.date_naive() - Duration::days(40);
+        let old = Goal::new("x".to_string(), GoalLevel::Daily, local_midnight(start).timestamp_millis() + 1, None);
+        assert_eq!(
+            goal_route(&old, &now, 2),
+            PopoverRoute::History { date: local_midnight(start).timestamp_millis() },
+        );
+
+        let toggle = PopoverRoute::Toggle { goal: goal.clone(), shown_in: Box::new(goal_route(&goal, &now, 2)) };
+        let json = serde_json::to_value(&toggle).unwrap();
+        assert_eq!((json["view"].as_str(), json["shownIn"]["view"].as_str()), (Some("toggle"), Some("goals")));
+    }
+
+    #[test]
+    fn test_reflection_period_start() {
+        // 月曜始まりの 2026-W42 は 10/12（月）から
+        let start = reflection_period_start(GoalLevel::Weekly, Some("2026-W42"), 2).unwrap();
+        assert_eq!(start, local_midnight(NaiveDate::from_ymd_opt(2026, 10, 12).unwrap()).timestamp_millis());
+        assert!(reflection_period_start(GoalLevel::Monthly, Some("2026-13"), 2).is_err());
+
+        // キーがなければ直前に終わった期間
+        let previous = reflection_period_start(GoalLevel::Daily, None, 2).unwrap();
+        let today = local_midnight(Local::now().date_naive());
+        assert_eq!(previous, local_midnight(today.date_naive() - Duration::days(1)).timestamp_millis());
+    }
+}
@@ -0,0 +1,253 @@
+// System-wide keyboard shortcuts. They are registered from the backend so
+// they work while another application is focused. Bindings are stored in the
+// settings table as accelerators ("CommandOrControl+Alt+F").
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
+use crate::db::Database;
+use crate::quick_add;
+use crate::tray::open_popover;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyAction {
+    /// Show or hide the floating window
+    ToggleFloating,
+    OpenPopover,
+    /// Open the quick-add window
+    QuickAdd,
+    StartReflection,
+}
+
+impl HotkeyAction {
+    pub const ALL: [HotkeyAction; 4] = [
+        HotkeyAction::ToggleFloating,
+        HotkeyAction::OpenPopover,
+        HotkeyAction::QuickAdd,
+        HotkeyAction::StartReflection,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HotkeyAction::ToggleFloating => "toggle_floating",
+            HotkeyAction::OpenPopover => "open_popover",
+            HotkeyAction::QuickAdd => "quick_add",
+            HotkeyAction::StartReflection => "start_reflection",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.as_str() == id)
+    }
+
+    /// Settings key of the binding. An empty value disables the shortcut.
+    fn setting_key(&self) -> String {
+        format!("hotkey_{}", self.as_str())
+    }
+}
+
+/// A shortcut as shown in the settings
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyBinding {
+    pub action: HotkeyAction,
+    /// Accelerator, empty when disabled
+    pub shortcut: String,
+    /// Why the shortcut is not active, e.g. another application holds it
+    pub error: Option<String>,
+}
+
+/// Registration failures of the saved bindings, by action
+#[derive(Default)]
+pub struct HotkeyErrors(Mutex<HashMap<HotkeyAction, String>>);
+
+/// Parse an accelerator. Shortcuts need Ctrl, Alt or Cmd so they do not
+/// swallow ordinary typing in other applications.
+pub fn parse_shortcut(shortcut: &str) -> Result<Shortcut, String> {
+    let parsed: Shortcut = shortcut
+        .parse()
+        .map_err(|e| format!("Invalid shortcut \"{}\": {}", shortcut, e))?;
+    if parsed.mods.difference(Modifiers::SHIFT).is_empty() {
+        return Err(format!("Shortcut \"{}\" needs Ctrl, Alt or Cmd", shortcut));
+    }
+    Ok(parsed)
+}
+
+/// Another action already bound to `shortcut`. Accelerators are compared by
+/// the keys they press, so "Ctrl+Alt+F" and "control+alt+KeyF" conflict.
+pub fn find_conflict(
+    bindings: &[(HotkeyAction, String)],
+    action: HotkeyAction,
+    shortcut: &Shortcut,
+) -> Option<HotkeyAction> {
+    bindings
+        .iter()
+        .filter(|(other, _)| *other != action)
+        .find(|(_, bound)| parse_shortcut(bound).ok().as_ref() == Some(shortcut))
+        .map(|(other, _)| *other)
+}
+
+fn saved_bindings(db: &Database) -> Vec<(HotkeyAction, String)> {
+    HotkeyAction::ALL
+        .iter()
+        .map(|action| (*action, db.get_setting(&action.setting_key()).unwrap_or_default()))
+        .collect()
+}
+
+fn register<R: Runtime>(app: &AppHandle<R>, action: HotkeyAction, shortcut: Shortcut) -> Result<(), String> {
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                run(app, action);
+            }
+        })
+        // 他のアプリが同じキーを使っていると OS が登録を拒否する
+        .map_err(|e| format!("Could not register {}, it may be used by another application: {}", shortcut, e))
+}
+
+fn run<R: Runtime>(app: &AppHandle<R>, action: HotkeyAction) {
+    match action {
+        HotkeyAction::ToggleFloating => {
+            if let Some(window) = app.get_webview_window("main") {
+                if window.is_visible().unwrap_or(false) {
+                    let _ = window.hide();
+                } else {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
+        }
+        HotkeyAction::OpenPopover => {
+            if let Some(window) = app.get_webview_window("popover") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }
+        HotkeyAction::QuickAdd => quick_add::show(app),
+        HotkeyAction::StartReflection => open_popover(app, "reflection"),
+    }
+}
+
+/// Register the saved shortcuts. Failures are kept for the settings view.
+pub fn register_saved<R: Runtime>(app: &AppHandle<R>) {
+    let db = app.state::<Database>();
+    let mut errors = HashMap::new();
+
+    for (action, shortcut) in saved_bindings(&db) {
+        if shortcut.is_empty() {
+            continue;
+        }
+        if let Err(e) = parse_shortcut(&shortcut).and_then(|parsed| register(app, action, parsed)) {
+            eprintln!("[Hotkeys] {}", e);
+            errors.insert(action, e);
+        }
+    }
+
+    app.manage(HotkeyErrors(Mutex::new(errors)));
+}
+
+/// Every action with its shortcut
+pub fn bindings<R: Runtime>(app: &AppHandle<R>) -> Vec<HotkeyBinding> {
+    let errors = app.state::<HotkeyErrors>();
+    let errors = errors.0.lock().unwrap();
+
+    saved_bindings(&app.state::<Database>())
+        .into_iter()
+        .map(|(action, shortcut)| HotkeyBinding {
+            action,
+            shortcut,
+            error: errors.get(&action).cloned(),
+        })
+        .collect()
+}
+
+/// Bind `action` to `shortcut` at runtime, or disable it with an empty
+/// string. The previous binding stays active when the new one fails.
+pub fn rebind<R: Runtime>(app: &AppHandle<R>, action: HotkeyAction, shortcut: &str) -> Result<HotkeyBinding, String> {
+    let db = app.state::<Database>();
+    let shortcut = shortcut.trim();
+    let bindings = saved_bindings(&db);
+
+    // 1. 検証と Trivyn 内での重複チェック
+    let new = if shortcut.is_empty() {
+        None
+    } else {
+        let parsed = parse_shortcut(shortcut)?;
+        if let Some(other) = find_conflict(&bindings, action, &parsed) {
+            return Err(format!("\"{}\" is already used for {}", shortcut, other.as_str()));
+        }
+        Some(parsed)
+    };
+
+    // 2. 古い割り当てを外す（別のアクションと共有している場合は残す）
+    let old = bindings
+        .iter()
+        .find(|(bound, _)| *bound == action)
+        .and_then(|(_, old)| parse_shortcut(old).ok())
+        .filter(|old| app.global_shortcut().is_registered(*old))
+        .filter(|old| find_conflict(&bindings, action, old).is_none());
+    if let Some(old) = old {
+        app.global_shortcut().unregister(old).map_err(|e| e.to_string())?;
+    }
+
+    // 3. 新しい割り当てを登録し、失敗したら元に戻す
+    if let Some(new) = new {
+        if let Err(e) = register(app, action, new) {
+            if let Some(old) = old {
+                let _ = register(app, action, old);
+            }
+            return Err(e);
+        }
+    }
+
+    db.set_setting(&action.setting_key(), shortcut)
+        .map_err(|e| e.to_string())?;
+    app.state::<HotkeyErrors>().0.lock().unwrap().remove(&action);
+
+    Ok(HotkeyBinding {
+        action,
+        shortcut: shortcut.to_string(),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shortcut() {
+        assert!(parse_shortcut("CommandOrControl+Alt+F").is_ok());
+        assert!(parse_shortcut("Ctrl+Shift+Space").is_ok());
+        // 修飾キーなし・Shift のみは入力を奪うので不可
+        assert!(parse_shortcut("F").is_err());
+        assert!(parse_shortcut("Shift+F").is_err());
+        assert!(parse_shortcut("Ctrl+Alt+").is_err());
+        assert!(parse_shortcut("Ctrl+NoSuchKey").is_err());
+    }
+
+    #[test]
+    fn test_find_conflict() {
+        let bindings = vec![
+            (HotkeyAction::ToggleFloating, "Ctrl+Alt+F".to_string()),
+            (HotkeyAction::OpenPopover, String::new()),
+            (HotkeyAction::QuickAdd, "Ctrl+Alt+N".to_string()),
+        ];
+        let shortcut = parse_shortcut("alt+control+KeyF").unwrap();
+        assert_eq!(find_conflict(&bindings, HotkeyAction::QuickAdd, &shortcut), Some(HotkeyAction::ToggleFloating));
+        // 自分自身の割り当ては重複にならない
+        assert_eq!(find_conflict(&bindings, HotkeyAction::ToggleFloating, &shortcut), None);
+        assert_eq!(find_conflict(&bindings, HotkeyAction::OpenPopover, &parse_shortcut("Ctrl+Alt+P").unwrap()), None);
+    }
+
+    #[test]
+    fn test_action_ids() {
+        for action in HotkeyAction::ALL {
+            assert_eq!(HotkeyAction::from_id(action.as_str()), Some(action));
+        }
+        assert_eq!(HotkeyAction::QuickAdd.setting_key(), "hotkey_quick_add");
+    }
+}
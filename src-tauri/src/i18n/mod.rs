@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use serde_json::Value;
+use crate::db::Database;
+
+/// `(language, JSON)` of every file in src/i18n/locales, embedded by build.rs
+const LOCALES: &[(&str, &str)] = include!(concat!(env!("OUT_DIR"), "/locales.rs"));
+
+/// Languages tried after the requested one. The frontend falls back to
+/// Japanese; English comes first here because it is the better guess for
+/// languages without a translation.
+const FALLBACK_LANGUAGES: [&str; 2] = ["en", "ja"];
+
+/// Translations of one language, flattened to dotted keys ("tray.quit")
+type Catalog = HashMap<String, String>;
+
+fn catalogs() -> &'static HashMap<String, Catalog> {
+    static CATALOGS: OnceLock<HashMap<String, Catalog>> = OnceLock::new();
+    CATALOGS.get_or_init(|| {
+        LOCALES
+            .iter()
+            .filter_map(|(language, json)| match serde_json::from_str::<Value>(json) {
+                Ok(value) => {
+                    let mut catalog = Catalog::new();
+                    flatten("", &value, &mut catalog);
+                    Some((language.to_string(), catalog))
+                }
+                Err(e) => {
+                    eprintln!("[i18n] Invalid locale file {}.json: {}", language, e);
+                    None
+                }
+            })
+            .collect()
+    })
+}
+
+fn flatten(prefix: &str, value: &Value, catalog: &mut Catalog) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten(&key, value, catalog);
+            }
+        }
+        Value::String(text) => {
+            catalog.insert(prefix.to_string(), text.clone());
+        }
+        _ => {}
+    }
+}
+
+/// Language of the operating system as a BCP 47 tag, e.g. "ja-JP"
+pub fn system_language() -> Option<String> {
+    sys_locale::get_locale()
+}
+
+/// A UI language with its fallback chain, resolving translations from the
+/// shared locale files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale {
+    /// Languages to look keys up in, most specific first. Only languages
+    /// that have a catalog are kept.
+    chain: Vec<String>,
+}
+
+impl Locale {
+    /// Locale for a `language` setting: "system" or a tag like "en", "ja"
+    /// or "pt-BR"
+    pub fn from_setting(language: &str) -> Self {
+        if language == "system" {
+            Self::from_tag(system_language().as_deref().unwrap_or(""))
+        } else {
+            Self::from_tag(language)
+        }
+    }
+
+    /// Locale for the language setting stored in `db`
+    pub fn for_db(db: &Database) -> Self {
+        Self::from_setting(&db.get_setting("language").unwrap_or_else(|_| "system".to_string()))
+    }
+
+    /// "pt-BR" tries "pt-BR", then "pt", then the fallback languages.
+    /// POSIX style tags ("ja_JP.UTF-8") are accepted too.
+    fn from_tag(tag: &str) -> Self {
+        let tag = tag.split('.').next().unwrap_or("").replace('_', "-");
+        let base = tag.split('-').next().unwrap_or("").to_string();
+
+        let mut chain: Vec<String> = Vec::new();
+        for candidate in [tag.clone(), base].into_iter().chain(FALLBACK_LANGUAGES.map(String::from)) {
+            let known = catalogs().keys().find(|language| language.eq_ignore_ascii_case(&candidate));
+            if let Some(language) = known {
+                if !chain.contains(language) {
+                    chain.push(language.clone());
+                }
+            }
+        }
+        Self { chain }
+    }
+
+    /// Language the UI is shown in, e.g. "en"
+    pub fn language(&self) -> &str {
+        self.chain.first().map_or(FALLBACK_LANGUAGES[0], String::as_str)
+    }
+
+    fn lookup(&self, key: &str) -> Option<&'static str> {
+        self.chain
+            .iter()
+            .find_map(|language| catalogs().get(language)?.get(key))
+            .map(String::as_str)
+    }
+
+    /// Translation of `key`, or the key itself when no language has it
+    pub fn t(&self, key: &str) -> String {
+        self.t_with(key, &[])
+    }
+
+    /// Translation of `key` with `{{name}}` placeholders filled in
+    pub fn t_with(&self, key: &str, args: &[(&str, String)]) -> String {
+        let mut text = self.lookup(key).unwrap_or(key).to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{{{}}}}}", name), value);
+        }
+        text
+    }
+
+    /// Plural aware translation: `key_one` or `key_other` as in i18next,
+    /// falling back to `key`. `{{count}}` is filled in.
+    pub fn t_count(&self, key: &str, count: usize, args: &[(&str, String)]) -> String {
+        let category = if count == 1 { "one" } else { "other" };
+        let mut args = args.to_vec();
+        args.push(("count", count.to_string()));
+
+        let plural_key = [format!("{}_{}", key, category), format!("{}_other", key)]
+            .into_iter()
+            .find(|k| self.lookup(k).is_some())
+            .unwrap_or_else(|| key.to_string());
+        self.t_with(&plural_key, &args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fallback_chain() {
+        assert_eq!(Locale::from_setting("en").t("tray.quit"), "Quit");
+        assert_eq!(Locale::from_setting("ja").t("tray.quit"), "終了");
+        // 地域付きのタグや POSIX 形式は基本言語に解決する
+        assert_eq!(Locale::from_tag("ja_JP.UTF-8").language(), "ja");
+        assert_eq!(Locale::from_tag("en-GB").language(), "en");
+        // 翻訳のない言語は英語、その後日本語
+        let french = Locale::from_tag("fr-FR");
+        assert_eq!(french.language(), "en");
+        assert_eq!(french.chain, ["en", "ja"]);
+        assert_eq!(french.t("no.such.key"), "no.such.key");
+    }
+
+    #[test]
+    fn test_interpolation_and_plurals() {
+        let en = Locale::from_setting("en");
+        let args = [("completed", "2".to_string()), ("total", "3".to_string())];
+        assert_eq!(en.t_with("tray.progress.daily", &args), "Today 2/3");
+
+        assert_eq!(en.t_count("settings.notifications.hours", 1, &[]), "1 hour");
+        assert_eq!(en.t_count("settings.notifications.hours", 3, &[]), "3 hours");
+    }
+
+    #[test]
+    fn test_locales_have_the_same_keys() {
+        let en = &catalogs()["en"];
+        for (language, catalog) in catalogs() {
+            // 複数形の形は言語ごとに異なるので比較しない
+            let base = |key: &String| key.trim_end_matches("_one").trim_end_matches("_other").to_string();
+            let missing: Vec<_> = en
+                .keys()
+                .map(base)
+                .filter(|key| !catalog.contains_key(key) && !catalog.keys().any(|k| base(k) == *key))
+                .collect();
+            assert!(missing.is_empty(), "{} is missing {:?}", language, missing);
+        }
+    }
+}
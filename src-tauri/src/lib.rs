@@ -3,3 +3,14 @@ pub mod db;
 pub mod commands;
 pub mod tray;
 pub mod window;
+pub mod undo;
+pub mod stats;
+pub mod review;
+pub mod notifications;
+pub mod reflection_reminder;
+pub mod planning_reminder;
+pub mod i18n;
+pub mod cli;
+pub mod deep_link;
+pub mod hotkeys;
+pub mod quick_add;
@@ -2,6 +2,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use tauri::Manager;
+use tauri_plugin_deep_link::DeepLinkExt;
 
 mod models;
 mod db;
@@ -9,9 +10,27 @@ mod commands;
 mod tray;
 mod window;
 mod reflection_reminder;
+mod undo;
+mod stats;
+mod review;
+mod scheduler;
+mod notifications;
+mod planning_reminder;
+mod i18n;
+mod cli;
+mod deep_link;
+mod hotkeys;
+mod quick_add;
 
 fn main() {
+    let launch_args = cli::LaunchArgs::from_env();
+
     tauri::Builder::default()
+        // Must come first: a second launch hands its arguments to the
+        // running instance and exits before opening the database
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            cli::apply_forwarded(app, argv);
+        }))
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_autostart::init(
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
@@ -19,11 +38,18 @@ fn main() {
         ))
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
-        .setup(|app| {
-            // Initialize database
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .manage(undo::UndoStack::new())
+        .manage(deep_link::PendingRoute::default())
+        .manage(quick_add::PendingGoal::default())
+        .setup(move |app| {
+            // Initialize database (--data-dir selects another one)
             let app_handle = app.handle().clone();
+            let data_dir = launch_args.data_dir.clone();
             tauri::async_runtime::block_on(async move {
-                if let Err(e) = db::init_database(&app_handle).await {
+                if let Err(e) = db::init_database(&app_handle, data_dir).await {
                     eprintln!("Failed to initialize database: {}", e);
                 }
             });
@@ -33,6 +59,9 @@ fn main() {
                 eprintln!("Failed to create system tray: {}", e);
             }
 
+            // Global shortcuts saved in settings
+            hotkeys::register_saved(app.handle());
+
             // Setup main window
             if let Err(e) = window::setup_main_window(app.handle()) {
                 eprintln!("Failed to setup main window: {}", e);
@@ -43,30 +72,43 @@ fn main() {
                 eprintln!("Failed to setup popover window: {}", e);
             }
 
-            // Show window on startup
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.show();
+            // Setup quick-add window
+            if let Err(e) = window::setup_quick_add_window(app.handle()) {
+                eprintln!("Failed to setup quick-add window: {}", e);
             }
 
-            // Initialize reflection reminder
+            // Reminders are shown as desktop notifications
             let db_state: tauri::State<db::Database> = app.state();
-            let reminder = std::sync::Arc::new(reflection_reminder::ReflectionReminder::new(
+            let notification_center = std::sync::Arc::new(notifications::NotificationCenter::new(
                 db_state.inner().clone(),
+                std::sync::Arc::new(notifications::TauriNotifier::new(app.handle().clone())),
             ));
+            app.manage(notification_center.clone());
 
-            // Run initial check
-            let app_handle_clone = app.handle().clone();
-            if let Err(e) = reminder.check_and_notify(&app_handle_clone) {
-                eprintln!("Initial reflection check failed: {}", e);
-            }
+            // Start background jobs (reminders, trash purge, backups, update checks)
+            let mut scheduler = scheduler::Scheduler::new(
+                db_state.inner().clone(),
+                std::sync::Arc::new(scheduler::SystemClock),
+            );
+            scheduler::jobs::register_default_jobs(&mut scheduler, db_state.inner(), notification_center);
 
-            // Start background checker
             let app_handle = app.handle().clone();
-            let reminder_clone = reminder.clone();
-            tauri::async_runtime::spawn(async move {
-                reflection_reminder::background::start_background_checker(reminder_clone, app_handle).await;
+            tauri::async_runtime::spawn(scheduler::run_loop(std::sync::Arc::new(scheduler), app_handle));
+
+            // trivyn:// links. macOS sends them as events; Windows and Linux
+            // pass them as arguments (see cli)
+            #[cfg(any(windows, target_os = "linux"))]
+            if let Err(e) = app.deep_link().register_all() {
+                eprintln!("Failed to register trivyn:// links: {}", e);
+            }
+            let app_handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                deep_link::open_urls(&app_handle, event.urls());
             });
 
+            // Show windows and run actions asked for on the command line
+            cli::apply(app.handle(), &launch_args);
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -75,10 +117,23 @@ fn main() {
             commands::goals::toggle_goal_completion,
             commands::goals::update_goal,
             commands::goals::delete_goal,
+            commands::goals::get_trashed_goals,
+            commands::goals::restore_goal,
+            commands::goals::purge_goal,
+            commands::goals::empty_trash,
+            commands::quick_add::preview_quick_add,
+            commands::quick_add::commit_quick_add,
+            commands::quick_add::preview_quick_add_goal,
+            commands::quick_add::commit_quick_add_goal,
+            commands::quick_add::take_quick_add_goal,
             commands::settings::get_setting,
             commands::settings::set_setting,
             commands::settings::set_language,
+            commands::settings::get_system_language,
             commands::settings::set_theme,
+            commands::settings::set_tray_show_weekly_goals,
+            commands::settings::get_hotkeys,
+            commands::settings::set_hotkey,
             commands::settings::get_all_settings,
             commands::settings::enable_autostart,
             commands::settings::disable_autostart,
@@ -86,12 +141,39 @@ fn main() {
             commands::reflections::get_reflection,
             commands::reflections::save_reflection,
             commands::reflections::get_reflections_by_level,
+            commands::reflections::get_daily_reflections,
+            commands::reflections::get_reflections_in_range,
+            commands::reflections::get_reflection_last_year,
+            commands::reflections::get_reflection_timeline,
+            commands::reflections::get_reflection_revisions,
+            commands::reflections::get_reflection_prompts,
+            commands::reflections::set_reflection_prompts,
             commands::periods::is_goal_in_period,
             commands::periods::get_period_start,
             commands::periods::get_week_key,
             commands::periods::get_month_key,
+            commands::periods::get_day_key,
+            commands::search::search,
+            commands::undo::undo,
+            commands::undo::redo,
+            commands::undo::get_undo_state,
+            commands::goal_events::get_goal_timeline,
+            commands::goal_events::get_period_timeline,
+            commands::stats::get_stats,
+            commands::stats::get_alignment,
+            commands::reviews::get_review_report,
+            commands::reviews::export_review_report,
+            commands::notifications::handle_reminder_action,
+            commands::notifications::get_reminder_status,
+            commands::notifications::snooze_reminders,
+            commands::notifications::skip_reminders_for_week,
+            commands::notifications::pause_reminders,
+            commands::notifications::resume_reminders,
+            commands::notifications::get_quiet_hours,
+            commands::notifications::set_quiet_hours,
             commands::window::resize_window_from_top,
             commands::window::resize_popover,
+            commands::window::take_popover_route,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
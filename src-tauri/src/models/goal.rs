@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Goal {
     pub id: String,
@@ -13,6 +13,8 @@ pub struct Goal {
     pub period_start: i64,
     pub parent_goal_id: Option<String>,
     pub note: Option<String>,
+    /// Set while the goal is in the trash
+    pub deleted_at: Option<i64>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -54,6 +56,7 @@ impl Goal {
             period_start,
             parent_goal_id,
             note: None,
+            deleted_at: None,
         }
     }
 }
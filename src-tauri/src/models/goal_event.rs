@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use crate::models::GoalLevel;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GoalEventType {
+    Created,
+    Renamed,
+    Completed,
+    Uncompleted,
+    Relinked,
+    Moved,
+    Deleted,
+    Restored,
+    Purged,
+}
+
+impl GoalEventType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            GoalEventType::Created => "created",
+            GoalEventType::Renamed => "renamed",
+            GoalEventType::Completed => "completed",
+            GoalEventType::Uncompleted => "uncompleted",
+            GoalEventType::Relinked => "relinked",
+            GoalEventType::Moved => "moved",
+            GoalEventType::Deleted => "deleted",
+            GoalEventType::Restored => "restored",
+            GoalEventType::Purged => "purged",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "created" => Some(GoalEventType::Created),
+            "renamed" => Some(GoalEventType::Renamed),
+            "completed" => Some(GoalEventType::Completed),
+            "uncompleted" => Some(GoalEventType::Uncompleted),
+            "relinked" => Some(GoalEventType::Relinked),
+            "moved" => Some(GoalEventType::Moved),
+            "deleted" => Some(GoalEventType::Deleted),
+            "restored" => Some(GoalEventType::Restored),
+            "purged" => Some(GoalEventType::Purged),
+            _ => None,
+        }
+    }
+}
+
+/// One entry of the append-only goal activity log.
+///
+/// `old_value`/`new_value` depend on the event type: titles for
+/// created/renamed, `completed_at` for completed/uncompleted, parent ids for
+/// relinked and period starts for moved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalEvent {
+    pub id: i64,
+    pub goal_id: String,
+    pub event_type: GoalEventType,
+    pub level: GoalLevel,
+    pub period_start: i64,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub occurred_at: i64,
+    /// Current title of the goal, or the last known one if it was purged
+    pub goal_title: Option<String>,
+}
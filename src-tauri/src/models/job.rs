@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// Persisted run state of a scheduled job
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobState {
+    pub job_id: String,
+    pub last_run_at: Option<i64>,
+    /// When the job is due next, as computed after its last run
+    pub next_run_at: Option<i64>,
+    /// Error of the last run, if it failed
+    pub last_error: Option<String>,
+    pub run_count: u32,
+}
@@ -1,6 +1,20 @@
 pub mod goal;
 pub mod settings;
 pub mod reflection;
+pub mod search;
+pub mod goal_event;
+pub mod stats;
+pub mod review;
+pub mod job;
+pub mod reminder;
 
 pub use goal::{Goal, GoalLevel};
-pub use reflection::Reflection;
+pub use reflection::{Reflection, ReflectionDetails, ReflectionPrompt, ReflectionRevision};
+pub use goal_event::{GoalEvent, GoalEventType};
+pub use stats::{
+    AlignmentReport, GoalStats, LevelCompletion, LevelCompletionTime, LevelStreak, ParentAlignment,
+};
+pub use job::JobState;
+pub use reminder::{QuietWindow, ReminderStatus};
+pub use review::{ReviewGoal, ReviewReport};
+pub use search::{SearchFilters, SearchHit, SearchSource};
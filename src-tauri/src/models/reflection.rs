@@ -1,7 +1,8 @@
+use std::collections::BTreeMap;
 use serde::{Deserialize, Serialize};
 use crate::models::GoalLevel;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Reflection {
     pub id: Option<i64>,
@@ -10,7 +11,68 @@ pub struct Reflection {
     pub insight_1: Option<String>,
     pub insight_2: Option<String>,
     pub insight_3: Option<String>,
+    #[serde(flatten)]
+    pub details: ReflectionDetails,
     pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Content of a reflection before one of its edits
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReflectionRevision {
+    pub id: i64,
+    pub reflection_id: i64,
+    pub insight_1: Option<String>,
+    pub insight_2: Option<String>,
+    pub insight_3: Option<String>,
+    #[serde(flatten)]
+    pub details: ReflectionDetails,
+    /// When this content was replaced
+    pub revised_at: i64,
+}
+
+/// Optional parts of a reflection beyond the three insights
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReflectionDetails {
+    /// Overall rating of the period, 1–5
+    pub rating: Option<u8>,
+    /// 1–5
+    pub mood: Option<u8>,
+    /// 1–5
+    pub energy: Option<u8>,
+    /// Free-form notes
+    pub body: Option<String>,
+    /// Answers to custom prompts, keyed by prompt id
+    #[serde(default)]
+    pub answers: BTreeMap<String, String>,
+}
+
+impl ReflectionDetails {
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in [("rating", self.rating), ("mood", self.mood), ("energy", self.energy)] {
+            if value.is_some_and(|v| !(1..=5).contains(&v)) {
+                return Err(format!("{} must be between 1 and 5", name));
+            }
+        }
+        if self.answers.keys().any(|id| id.trim().is_empty()) {
+            return Err("Answers must be keyed by a prompt id".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// A user-defined reflection question for one level
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReflectionPrompt {
+    pub id: String,
+    pub level: GoalLevel,
+    pub text: String,
+    pub position: i32,
+    /// Set once the prompt is removed from its set; answers to it stay readable
+    pub archived_at: Option<i64>,
 }
 
 impl Reflection {
@@ -21,6 +83,7 @@ impl Reflection {
         insight_2: Option<String>,
         insight_3: Option<String>,
     ) -> Self {
+        let now = chrono::Utc::now().timestamp_millis();
         Self {
             id: None,
             level,
@@ -28,7 +91,48 @@ impl Reflection {
             insight_1,
             insight_2,
             insight_3,
-            created_at: chrono::Utc::now().timestamp_millis(),
+            details: ReflectionDetails::default(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn with_details(mut self, details: ReflectionDetails) -> Self {
+        self.details = details;
+        self
+    }
+
+    /// Same insights and details, ignoring ids and timestamps
+    pub fn has_same_content(&self, other: &Reflection) -> bool {
+        self.insight_1 == other.insight_1
+            && self.insight_2 == other.insight_2
+            && self.insight_3 == other.insight_3
+            && self.details == other.details
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_details() {
+        assert!(ReflectionDetails::default().validate().is_ok());
+
+        let details = ReflectionDetails { rating: Some(5), mood: Some(1), energy: Some(3), ..Default::default() };
+        assert!(details.validate().is_ok());
+        for details in [
+            ReflectionDetails { rating: Some(0), ..Default::default() },
+            ReflectionDetails { mood: Some(6), ..Default::default() },
+            ReflectionDetails { energy: Some(9), ..Default::default() },
+        ] {
+            assert!(details.validate().is_err());
         }
+
+        let mut details = ReflectionDetails::default();
+        details.answers.insert("prompt-1".into(), "More focus".into());
+        assert!(details.validate().is_ok());
+        details.answers.insert(" ".into(), "No prompt".into());
+        assert!(details.validate().is_err());
     }
 }
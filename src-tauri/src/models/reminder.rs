@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// A daily window in which reminders are held back. Starts on `weekday`
+/// and runs past midnight when `end` is not after `start`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietWindow {
+    /// 0 = Sunday, ..., 6 = Saturday
+    pub weekday: u8,
+    /// "HH:MM"
+    pub start: String,
+    /// "HH:MM"
+    pub end: String,
+}
+
+/// Why reminders are currently held back, and until when (ms)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderStatus {
+    pub snoozed_until: Option<i64>,
+    pub paused_until: Option<i64>,
+    /// End of the quiet window `now` falls in
+    pub quiet_until: Option<i64>,
+    /// When held-back reminders will be delivered, taking every hold into account
+    pub held_until: Option<i64>,
+    /// Week key reminders are skipped for
+    pub skipped_week: Option<String>,
+    /// Reminders waiting to be delivered
+    pub pending: usize,
+}
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use crate::models::{Goal, GoalLevel, LevelStreak, Reflection};
+
+/// A goal of the reviewed period together with the goals linked below it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewGoal {
+    pub goal: Goal,
+    pub children: Vec<Goal>,
+    pub children_completed: u32,
+}
+
+/// Review of a closing week or month
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewReport {
+    pub level: GoalLevel,
+    pub period_key: String,
+    pub period_start: i64,
+    pub period_end: i64,
+    pub generated_at: i64,
+    pub goals: Vec<ReviewGoal>,
+    pub completed: u32,
+    /// Unfinished goals that may be carried over into the next period
+    pub carry_overs: Vec<Goal>,
+    pub streak: LevelStreak,
+    /// Reflection written for the period before the reviewed one
+    pub previous_reflection: Option<Reflection>,
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use crate::models::GoalLevel;
+
+/// Where a search hit comes from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchSource {
+    Goal,
+    Reflection,
+}
+
+impl SearchSource {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SearchSource::Goal => "goal",
+            SearchSource::Reflection => "reflection",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "goal" => Some(SearchSource::Goal),
+            "reflection" => Some(SearchSource::Reflection),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFilters {
+    pub level: Option<GoalLevel>,
+    pub source: Option<SearchSource>,
+    /// Inclusive lower bound on the period start (milliseconds)
+    pub from: Option<i64>,
+    /// Exclusive upper bound on the period start (milliseconds)
+    pub to: Option<i64>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub source: SearchSource,
+    /// Goal id, or reflection id as a string
+    pub id: String,
+    pub level: GoalLevel,
+    pub period_key: String,
+    /// Goal title, or empty for reflections
+    pub title: String,
+    /// Matched text with hits wrapped in `<mark>` tags
+    pub snippet: String,
+    pub is_completed: Option<bool>,
+    /// Lower is better (bm25)
+    pub rank: f64,
+}
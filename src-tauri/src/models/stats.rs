@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use crate::models::{Goal, GoalLevel};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelCompletion {
+    pub level: GoalLevel,
+    pub total: u32,
+    pub completed: u32,
+    /// `completed / total`, or 0 when there were no goals
+    pub rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelStreak {
+    pub level: GoalLevel,
+    /// Consecutive periods with all three goals done, up to the current one.
+    /// The current period only counts once it is complete.
+    pub current: u32,
+    pub longest: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelCompletionTime {
+    pub level: GoalLevel,
+    /// Average of `completed_at - created_at` in milliseconds
+    pub average_ms: Option<i64>,
+    pub sample_size: u32,
+}
+
+/// Everything the history view needs, computed in one go
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoalStats {
+    pub from: i64,
+    pub to: i64,
+    pub completion: Vec<LevelCompletion>,
+    /// Streaks span the whole history, not only `[from, to)`
+    pub streaks: Vec<LevelStreak>,
+    pub time_to_complete: Vec<LevelCompletionTime>,
+    /// Completions per weekday, index 0 = Sunday
+    pub completions_by_weekday: Vec<u32>,
+    /// Completions per local hour of day, index 0 = 00:00-00:59
+    pub completions_by_hour: Vec<u32>,
+    /// How often a completed goal was unchecked again
+    pub uncompleted_count: u32,
+}
+
+/// How one weekly/monthly goal was backed by lower-level goals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParentAlignment {
+    pub goal: Goal,
+    /// Goals one level down linked to this goal
+    pub linked_children: u32,
+    pub completed_children: u32,
+    /// Daily goals working towards this goal, directly or through a weekly goal
+    pub daily_effort: u32,
+    pub daily_completed: u32,
+}
+
+/// Alignment between a week or month and the goals below it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlignmentReport {
+    pub level: GoalLevel,
+    pub period_key: String,
+    pub period_start: i64,
+    pub period_end: i64,
+    /// Daily goals in the period
+    pub daily_total: u32,
+    /// Daily goals in the period linked to a weekly goal
+    pub daily_linked: u32,
+    /// `daily_linked / daily_total`, or 0 when there were no daily goals
+    pub daily_linked_share: f64,
+    /// Children linked to this period's goals, and how many of them were done
+    pub linked_children: u32,
+    pub linked_children_completed: u32,
+    pub parents: Vec<ParentAlignment>,
+    /// Goals of this period that received no daily effort at all
+    pub neglected: Vec<Goal>,
+}
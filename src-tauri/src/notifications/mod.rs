@@ -0,0 +1,550 @@
+use std::sync::Arc;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_notification::NotificationExt;
+use crate::commands::goals::get_week_start_setting;
+use crate::commands::periods::{local_midnight, parse_time_of_day, week_key};
+use crate::db::Database;
+use crate::i18n::Locale;
+use crate::models::{QuietWindow, ReminderStatus};
+
+/// What a reminder is about
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReminderKind {
+    #[default]
+    Reflection,
+    Planning,
+}
+
+impl ReminderKind {
+    /// Action type the notification buttons of this kind are registered
+    /// under by the frontend
+    pub fn action_type(&self) -> &'static str {
+        match self {
+            ReminderKind::Reflection => "reflection-reminder",
+            ReminderKind::Planning => "planning-reminder",
+        }
+    }
+}
+
+/// What the user can do with a reminder, from the notification (where the
+/// platform supports buttons) or from the tray menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReminderAction {
+    /// Open the popover on the reflection tab
+    ReflectNow,
+    /// Open the popover on the goals tab
+    PlanNow,
+    /// Show the reminder again in an hour
+    Snooze,
+    /// No more reminders until the next week starts
+    SkipWeek,
+}
+
+impl ReminderAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReminderAction::ReflectNow => "reflect_now",
+            ReminderAction::PlanNow => "plan_now",
+            ReminderAction::Snooze => "snooze",
+            ReminderAction::SkipWeek => "skip_week",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "reflect_now" => Some(ReminderAction::ReflectNow),
+            "plan_now" => Some(ReminderAction::PlanNow),
+            "snooze" => Some(ReminderAction::Snooze),
+            "skip_week" => Some(ReminderAction::SkipWeek),
+            _ => None,
+        }
+    }
+}
+
+/// A reminder shown as a desktop notification
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderNotification {
+    #[serde(default)]
+    pub kind: ReminderKind,
+    pub title: String,
+    pub body: String,
+}
+
+impl ReminderNotification {
+    /// Reflection reminder for the periods that just became due
+    pub fn reflection(locale: &Locale, weekly: bool, monthly: bool, daily: bool) -> Self {
+        let body = match (weekly, monthly) {
+            (true, true) => locale.t("reminder.reflectionWeekAndMonth"),
+            (true, false) => locale.t("reminder.reflectionWeek"),
+            (false, true) => locale.t("reminder.reflectionMonth"),
+            (false, false) if daily => locale.t("reminder.reflectionDay"),
+            _ => String::new(),
+        };
+
+        Self {
+            kind: ReminderKind::Reflection,
+            title: locale.t("reminder.reflectionTitle"),
+            body,
+        }
+    }
+}
+
+/// Delivers reminders to the user
+pub trait Notifier: Send + Sync {
+    fn notify(&self, notification: &ReminderNotification) -> Result<(), String>;
+}
+
+/// Native desktop notifications through the notification plugin
+pub struct TauriNotifier<R: Runtime> {
+    app: AppHandle<R>,
+}
+
+impl<R: Runtime> TauriNotifier<R> {
+    pub fn new(app: AppHandle<R>) -> Self {
+        Self { app }
+    }
+}
+
+impl<R: Runtime> Notifier for TauriNotifier<R> {
+    /// Show the notification without touching any window. The popover opens
+    /// when the user picks the reminder's action here or in the tray menu.
+    fn notify(&self, notification: &ReminderNotification) -> Result<(), String> {
+        self.app
+            .notification()
+            .builder()
+            .title(&notification.title)
+            .body(&notification.body)
+            .action_type_id(notification.kind.action_type())
+            .show()
+            .map_err(|e| format!("Failed to show notification: {}", e))?;
+
+        // トレイメニューに対応中のリマインダーを出す
+        let _ = self.app.emit("reminders-updated", ());
+        Ok(())
+    }
+}
+
+/// Carry out a reminder action chosen in a notification or the tray menu
+pub fn handle_action<R: Runtime>(app: &AppHandle<R>, action: ReminderAction) -> Result<(), String> {
+    let notifications = app.state::<Arc<NotificationCenter>>();
+    let now = Local::now();
+
+    match action {
+        ReminderAction::ReflectNow => {
+            notifications.dismiss()?;
+            crate::tray::open_popover(app, "reflection");
+        }
+        ReminderAction::PlanNow => {
+            notifications.dismiss()?;
+            crate::tray::open_popover(app, "goals");
+        }
+        ReminderAction::Snooze => notifications.snooze((now + Duration::hours(1)).timestamp_millis())?,
+        ReminderAction::SkipWeek => notifications.skip_week(now.timestamp_millis())?,
+    }
+
+    app.emit("reminders-updated", ())
+        .map_err(|e| format!("Failed to emit event: {}", e))
+}
+
+/// Sends reminders, holding them back while snoozed, paused, in quiet hours
+/// or skipped for the week. Held-back reminders are delivered once the
+/// hold ends (see `deliver_pending`).
+pub struct NotificationCenter {
+    db: Database,
+    notifier: Arc<dyn Notifier>,
+}
+
+impl NotificationCenter {
+    pub fn new(db: Database, notifier: Arc<dyn Notifier>) -> Self {
+        Self { db, notifier }
+    }
+
+    /// Show `notification` now, or queue it if reminders are held back.
+    /// Reminders in a skipped week are dropped. Returns whether it was shown.
+    pub fn deliver(&self, notification: &ReminderNotification, now: i64) -> Result<bool, String> {
+        let json = serde_json::to_string(notification).map_err(|e| e.to_string())?;
+        self.set_setting("last_reminder_notification", &json)?;
+
+        if self.is_skipped_for_week(now) {
+            return Ok(false);
+        }
+        if self.held_until(now).is_some() {
+            self.queue(notification)?;
+            return Ok(false);
+        }
+
+        self.set_open(Some(notification.kind))?;
+        self.notifier.notify(notification)?;
+        Ok(true)
+    }
+
+    /// Deliver the queued reminders once nothing holds them back anymore.
+    /// Returns how many were shown.
+    pub fn deliver_pending(&self, now: i64) -> Result<usize, String> {
+        let mut pending = self.get_pending();
+        if pending.is_empty() || self.held_until(now).is_some() {
+            return Ok(0);
+        }
+
+        if self.is_skipped_for_week(now) {
+            self.set_pending(&[])?;
+            return Ok(0);
+        }
+
+        // 表示できたものから順にキューから外す（失敗したら残りは次回に）
+        let mut shown = 0;
+        while let Some(notification) = pending.first() {
+            self.set_open(Some(notification.kind))?;
+            self.notifier.notify(notification)?;
+            pending.remove(0);
+            self.set_pending(&pending)?;
+            shown += 1;
+        }
+        Ok(shown)
+    }
+
+    /// Hold back reminders until `until` (ms) and show the last one again then
+    pub fn snooze(&self, until: i64) -> Result<(), String> {
+        let json = self.db.get_setting("last_reminder_notification").unwrap_or_default();
+        if let Ok(last) = serde_json::from_str::<ReminderNotification>(&json) {
+            self.queue(&last)?;
+        }
+        self.set_open(None)?;
+        self.set_setting("reminder_snoozed_until", &until.to_string())
+    }
+
+    /// Do not disturb until `until` (ms). Reminders due meanwhile are queued.
+    pub fn pause(&self, until: i64) -> Result<(), String> {
+        self.set_setting("reminders_paused_until", &until.to_string())
+    }
+
+    /// End a pause and any snooze
+    pub fn resume(&self) -> Result<(), String> {
+        self.set_setting("reminders_paused_until", "0")?;
+        self.set_setting("reminder_snoozed_until", "0")
+    }
+
+    /// No more reminders until the week containing `now` is over
+    pub fn skip_week(&self, now: i64) -> Result<(), String> {
+        let week_key = self.week_key(now)?;
+        self.set_setting("reminder_skipped_week", &week_key)?;
+        self.set_setting("reminder_snoozed_until", "0")?;
+        self.set_open(None)?;
+        self.set_pending(&[])
+    }
+
+    /// Kind of the reminder shown last, until the user acts on it
+    pub fn open_reminder(&self) -> Option<ReminderKind> {
+        let json = self.db.get_setting("open_reminder").unwrap_or_default();
+        serde_json::from_str(&json).ok()
+    }
+
+    /// The user opened the reminder's view
+    pub fn dismiss(&self) -> Result<(), String> {
+        self.set_open(None)
+    }
+
+    pub fn get_quiet_hours(&self) -> Vec<QuietWindow> {
+        let json = self.db.get_setting("quiet_hours").unwrap_or_default();
+        serde_json::from_str(&json).unwrap_or_default()
+    }
+
+    pub fn set_quiet_hours(&self, windows: &[QuietWindow]) -> Result<(), String> {
+        for window in windows {
+            if window.weekday > 6 {
+                return Err(format!("Invalid weekday: {}", window.weekday));
+            }
+            for time in [&window.start, &window.end] {
+                parse_time_of_day(time).ok_or_else(|| format!("Invalid time: {}", time))?;
+            }
+        }
+        let json = serde_json::to_string(windows).map_err(|e| e.to_string())?;
+        self.set_setting("quiet_hours", &json)
+    }
+
+    pub fn status(&self, now: i64) -> ReminderStatus {
+        let after_now = |key: &str| Some(self.get_timestamp(key)).filter(|&t| t > now);
+        let skipped = self.db.get_setting("reminder_skipped_week").unwrap_or_default();
+
+        ReminderStatus {
+            snoozed_until: after_now("reminder_snoozed_until"),
+            paused_until: after_now("reminders_paused_until"),
+            quiet_until: DateTime::from_timestamp_millis(now)
+                .and_then(|dt| quiet_until(&self.get_quiet_hours(), &dt.with_timezone(&Local)))
+                .map(|dt| dt.timestamp_millis()),
+            held_until: self.held_until(now),
+            skipped_week: self.week_key(now).ok().filter(|key| *key == skipped),
+            pending: self.get_pending().len(),
+        }
+    }
+
+    /// When reminders may be shown again, if something holds them back at
+    /// `now`. Holds that run into each other (a pause ending inside quiet
+    /// hours) are followed to the end.
+    fn held_until(&self, now: i64) -> Option<i64> {
+        let snoozed = self.get_timestamp("reminder_snoozed_until");
+        let paused = self.get_timestamp("reminders_paused_until");
+        let quiet_hours = self.get_quiet_hours();
+
+        let mut until = now;
+        loop {
+            let quiet = DateTime::from_timestamp_millis(until)
+                .and_then(|dt| quiet_until(&quiet_hours, &dt.with_timezone(&Local)))
+                .map_or(0, |dt| dt.timestamp_millis());
+            let next = [snoozed, paused, quiet].into_iter().filter(|&t| t > until).max();
+            match next {
+                Some(next) => until = next,
+                None => break,
+            }
+        }
+
+        (until > now).then_some(until)
+    }
+
+    fn queue(&self, notification: &ReminderNotification) -> Result<(), String> {
+        let mut pending = self.get_pending();
+        if !pending.contains(notification) {
+            pending.push(notification.clone());
+        }
+        self.set_pending(&pending)
+    }
+
+    fn set_open(&self, kind: Option<ReminderKind>) -> Result<(), String> {
+        let json = match kind {
+            Some(kind) => serde_json::to_string(&kind).map_err(|e| e.to_string())?,
+            None => String::new(),
+        };
+        self.set_setting("open_reminder", &json)
+    }
+
+    fn get_pending(&self) -> Vec<ReminderNotification> {
+        let json = self.db.get_setting("pending_reminders").unwrap_or_default();
+        serde_json::from_str(&json).unwrap_or_default()
+    }
+
+    fn set_pending(&self, pending: &[ReminderNotification]) -> Result<(), String> {
+        let json = serde_json::to_string(pending).map_err(|e| e.to_string())?;
+        self.set_setting("pending_reminders", &json)
+    }
+
+    fn week_key(&self, now: i64) -> Result<String, String> {
+        let dt = DateTime::from_timestamp_millis(now)
+            .ok_or("Invalid timestamp")?
+            .with_timezone(&Local);
+        Ok(week_key(&dt, get_week_start_setting(&self.db)))
+    }
+
+    fn get_timestamp(&self, key: &str) -> i64 {
+        self.db
+            .get_setting(key)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0)
+    }
+
+    fn is_skipped_for_week(&self, now: i64) -> bool {
+        let skipped = self.db.get_setting("reminder_skipped_week").unwrap_or_default();
+        self.week_key(now).is_ok_and(|key| key == skipped)
+    }
+
+    fn set_setting(&self, key: &str, value: &str) -> Result<(), String> {
+        self.db
+            .set_setting(key, value)
+            .map_err(|e| format!("Failed to update {}: {}", key, e))
+    }
+}
+
+/// End of the quiet window `at` falls in. Windows starting the day before
+/// are included so overnight windows work.
+pub fn quiet_until(windows: &[QuietWindow], at: &DateTime<Local>) -> Option<DateTime<Local>> {
+    let today = at.date_naive();
+    [today.pred_opt()?, today]
+        .into_iter()
+        .flat_map(|date| {
+            let weekday = date.weekday().num_days_from_sunday() as u8;
+            windows.iter().filter(move |w| w.weekday == weekday).map(move |w| (date, w))
+        })
+        .filter_map(|(date, window)| {
+            let start = parse_time_of_day(&window.start)?;
+            let end = parse_time_of_day(&window.end)?;
+            let end_date = if end <= start { date.succ_opt()? } else { date };
+            let from = local_midnight(date) + (start - NaiveTime::MIN);
+            let to = local_midnight(end_date) + (end - NaiveTime::MIN);
+            (from <= *at && *at < to).then_some(to)
+        })
+        .max()
+}
+
+/// Keeps every notification instead of showing it
+#[cfg(test)]
+#[derive(Default)]
+pub struct RecordingNotifier {
+    pub sent: std::sync::Mutex<Vec<ReminderNotification>>,
+}
+
+#[cfg(test)]
+impl Notifier for RecordingNotifier {
+    fn notify(&self, notification: &ReminderNotification) -> Result<(), String> {
+        self.sent.lock().unwrap().push(notification.clone());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn center() -> (NotificationCenter, Arc<RecordingNotifier>) {
+        let db = Database::new(":memory:".into()).unwrap();
+        db.migrate().unwrap();
+        let notifier = Arc::new(RecordingNotifier::default());
+        (NotificationCenter::new(db, notifier.clone()), notifier)
+    }
+
+    fn at(d: u32, h: u32, m: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 12, d, h, m, 0).unwrap()
+    }
+
+    fn window(weekday: u8, start: &str, end: &str) -> QuietWindow {
+        QuietWindow { weekday, start: start.to_string(), end: end.to_string() }
+    }
+
+    #[test]
+    fn test_snooze_defers_and_resends_notification() {
+        let (center, notifier) = center();
+        let notification = ReminderNotification::reflection(&Locale::from_setting("en"), true, false, false);
+        let now = at(8, 9, 0).timestamp_millis();
+        let hour = 60 * 60 * 1000;
+
+        assert!(center.deliver(&notification, now).unwrap());
+
+        // 1時間スヌーズ: 期限前は何も送らず、期限後に同じ通知を一度だけ再送
+        center.snooze(now + hour).unwrap();
+        assert_eq!(center.deliver_pending(now + hour - 1).unwrap(), 0);
+        assert_eq!(center.deliver_pending(now + hour).unwrap(), 1);
+        assert_eq!(center.deliver_pending(now + hour + 1).unwrap(), 0);
+
+        let sent = notifier.sent.lock().unwrap();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[1], notification);
+    }
+
+    #[test]
+    fn test_open_reminder_lasts_until_acted_on() {
+        let (center, _) = center();
+        let notification = ReminderNotification::reflection(&Locale::from_setting("en"), true, false, false);
+        let now = at(8, 9, 0).timestamp_millis();
+        assert_eq!(center.open_reminder(), None);
+
+        center.deliver(&notification, now).unwrap();
+        assert_eq!(center.open_reminder(), Some(ReminderKind::Reflection));
+        center.dismiss().unwrap();
+        assert_eq!(center.open_reminder(), None);
+
+        // スヌーズ中は閉じ、再送されたらまた開く
+        center.deliver(&notification, now).unwrap();
+        center.snooze(now + 1).unwrap();
+        assert_eq!(center.open_reminder(), None);
+        center.deliver_pending(now + 1).unwrap();
+        assert_eq!(center.open_reminder(), Some(ReminderKind::Reflection));
+        center.skip_week(now + 1).unwrap();
+        assert_eq!(center.open_reminder(), None);
+    }
+
+    #[test]
+    fn test_skip_week_silences_until_next_week() {
+        let (center, notifier) = center();
+        let notification = ReminderNotification::reflection(&Locale::from_setting("ja"), false, false, true);
+        // 週の開始は月曜（デフォルト）
+        let monday = at(8, 9, 0);
+
+        center.skip_week(monday.timestamp_millis()).unwrap();
+        let sunday = monday + chrono::Duration::days(6);
+        assert!(!center.deliver(&notification, sunday.timestamp_millis()).unwrap());
+        let next_monday = monday + chrono::Duration::days(7);
+        assert!(center.deliver(&notification, next_monday.timestamp_millis()).unwrap());
+        assert_eq!(notifier.sent.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_quiet_until_handles_overnight_windows() {
+        // 2025-12-08 は月曜日
+        let windows = [window(1, "22:00", "07:00"), window(2, "12:00", "13:00")];
+
+        assert_eq!(quiet_until(&windows, &at(8, 21, 59)), None);
+        assert_eq!(quiet_until(&windows, &at(8, 23, 0)), Some(at(9, 7, 0)));
+        assert_eq!(quiet_until(&windows, &at(9, 3, 0)), Some(at(9, 7, 0)));
+        assert_eq!(quiet_until(&windows, &at(9, 7, 0)), None);
+        assert_eq!(quiet_until(&windows, &at(9, 12, 30)), Some(at(9, 13, 0)));
+        // 日曜の夜には窓がない
+        assert_eq!(quiet_until(&windows, &at(7, 23, 0)), None);
+    }
+
+    #[test]
+    fn test_quiet_hours_and_pause_delay_delivery() {
+        let (center, notifier) = center();
+        center.set_quiet_hours(&[window(1, "22:00", "07:00")]).unwrap();
+        let notification = ReminderNotification::reflection(&Locale::from_setting("en"), true, false, false);
+
+        // 深夜のリマインダーは静音時間が終わるまで保留
+        assert!(!center.deliver(&notification, at(8, 23, 0).timestamp_millis()).unwrap());
+        assert_eq!(center.status(at(8, 23, 0).timestamp_millis()).held_until, Some(at(9, 7, 0).timestamp_millis()));
+
+        // 静音時間の終わりから一時停止が続くと、その終わりまで延びる
+        center.pause(at(9, 8, 0).timestamp_millis()).unwrap();
+        let status = center.status(at(9, 6, 0).timestamp_millis());
+        assert_eq!(status.held_until, Some(at(9, 8, 0).timestamp_millis()));
+        assert_eq!(status.pending, 1);
+        assert_eq!(center.deliver_pending(at(9, 7, 30).timestamp_millis()).unwrap(), 0);
+
+        assert_eq!(center.deliver_pending(at(9, 8, 0).timestamp_millis()).unwrap(), 1);
+        assert_eq!(*notifier.sent.lock().unwrap(), [notification]);
+        assert_eq!(center.status(at(9, 8, 0).timestamp_millis()), ReminderStatus::default());
+
+        assert!(center.set_quiet_hours(&[window(7, "22:00", "07:00")]).is_err());
+    }
+
+    /// Fails once `fail_after` notifications have been shown
+    struct FlakyNotifier {
+        fail_after: usize,
+        sent: std::sync::Mutex<usize>,
+    }
+
+    impl Notifier for FlakyNotifier {
+        fn notify(&self, _notification: &ReminderNotification) -> Result<(), String> {
+            let mut sent = self.sent.lock().unwrap();
+            if *sent >= self.fail_after {
+                return Err("unavailable".to_string());
+            }
+            *sent += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_failed_delivery_keeps_pending_reminders() {
+        let db = Database::new(":memory:".into()).unwrap();
+        db.migrate().unwrap();
+        let center = NotificationCenter::new(
+            db.clone(),
+            Arc::new(FlakyNotifier { fail_after: 1, sent: Default::default() }),
+        );
+        let locale = Locale::from_setting("en");
+        let now = at(8, 9, 0).timestamp_millis();
+
+        center.pause(now + 1).unwrap();
+        center.deliver(&ReminderNotification::reflection(&locale, true, false, false), now).unwrap();
+        center.deliver(&ReminderNotification::reflection(&locale, false, true, false), now).unwrap();
+        assert_eq!(center.status(now).pending, 2);
+
+        // 1件目は表示済みなので外し、表示できなかった2件目は残す
+        assert!(center.deliver_pending(now + 1).is_err());
+        assert_eq!(center.status(now + 1).pending, 1);
+        assert_eq!(center.get_pending()[0].body, locale.t("reminder.reflectionMonth"));
+    }
+}
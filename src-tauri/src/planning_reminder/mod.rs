@@ -0,0 +1,258 @@
+use std::sync::Arc;
+use crate::commands::goals::get_week_start_setting;
+use crate::commands::periods::{parse_time_of_day, period_key, period_range};
+use crate::db::Database;
+use crate::i18n::Locale;
+use crate::models::GoalLevel;
+use crate::notifications::{NotificationCenter, ReminderKind, ReminderNotification};
+use chrono::{DateTime, Duration, Local};
+
+/// Why a level needs attention
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NudgeKind {
+    /// Fewer goals than `planning_min_goals` are set for the period
+    Unset,
+    /// The period ends today and some of its goals are still open
+    Unfinished,
+}
+
+impl NudgeKind {
+    fn setting_key(&self, level: GoalLevel) -> String {
+        match self {
+            NudgeKind::Unset => format!("last_{}_planning_prompt", level.as_str()),
+            NudgeKind::Unfinished => format!("last_{}_wrap_up_prompt", level.as_str()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanningNudge {
+    pub level: GoalLevel,
+    pub kind: NudgeKind,
+    pub period_key: String,
+    pub goals: usize,
+    pub completed: usize,
+}
+
+/// Nudges to set goals for the current periods and to finish them before
+/// the periods end
+pub struct PlanningReminder {
+    db: Database,
+    notifications: Arc<NotificationCenter>,
+}
+
+impl PlanningReminder {
+    pub fn new(db: Database, notifications: Arc<NotificationCenter>) -> Self {
+        Self { db, notifications }
+    }
+
+    /// 計画リマインダーをチェックし、必要なら通知する（期間ごとに一度だけ）
+    pub fn check_and_notify(&self, now: i64) -> Result<Vec<PlanningNudge>, String> {
+        // 1. 設定チェック
+        if self.db.get_setting("planning_reminder_enabled").unwrap_or_default() != "true" {
+            return Ok(Vec::new());
+        }
+
+        // 2. 通知すべき期間を集める
+        let nudges = self.due_nudges(now)?;
+        if nudges.is_empty() {
+            return Ok(nudges);
+        }
+
+        // 3. まとめて一つの通知にする
+        let notification = notification(&Locale::for_db(&self.db), &nudges, self.min_goals());
+        if let Err(e) = self.notifications.deliver(&notification, now) {
+            eprintln!("[PlanningReminder] Failed to show notification: {}", e);
+        }
+
+        // 4. 表示履歴を更新
+        for nudge in &nudges {
+            self.mark_shown(nudge)?;
+        }
+
+        Ok(nudges)
+    }
+
+    /// Levels that need a nudge at `now` and were not nudged yet this period
+    fn due_nudges(&self, now: i64) -> Result<Vec<PlanningNudge>, String> {
+        let now_dt = DateTime::from_timestamp_millis(now)
+            .ok_or("Invalid timestamp")?
+            .with_timezone(&Local);
+        let week_start = get_week_start_setting(&self.db);
+        let min_goals = self.min_goals();
+        let plan_due = self.is_time_reached("planning_reminder_time", &now_dt);
+        let wrap_up_due = self.is_time_reached("planning_wrap_up_time", &now_dt);
+
+        let mut nudges = Vec::new();
+        for level in [GoalLevel::Monthly, GoalLevel::Weekly, GoalLevel::Daily] {
+            let (start, end) = period_range(level, &now_dt, week_start);
+            let key = period_key(level, now, week_start).ok_or("Invalid timestamp")?;
+            let goals = self
+                .db
+                .get_goals_in_range(level.as_str(), start.timestamp_millis(), end.timestamp_millis())
+                .map_err(|e| format!("Failed to get goals: {}", e))?;
+            let completed = goals.iter().filter(|g| g.is_completed).count();
+
+            let is_last_day = (end - Duration::milliseconds(1)).date_naive() == now_dt.date_naive();
+            let candidates = [
+                (NudgeKind::Unset, plan_due && goals.len() < min_goals),
+                (NudgeKind::Unfinished, wrap_up_due && is_last_day && completed < goals.len()),
+            ];
+
+            for (kind, due) in candidates {
+                if due && !self.has_shown_for_period(kind, level, &key) {
+                    nudges.push(PlanningNudge {
+                        level,
+                        kind,
+                        period_key: key.clone(),
+                        goals: goals.len(),
+                        completed,
+                    });
+                }
+            }
+        }
+
+        Ok(nudges)
+    }
+
+    fn min_goals(&self) -> usize {
+        self.db
+            .get_setting("planning_min_goals")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(3)
+    }
+
+    /// Whether the "HH:MM" time in `setting` has passed today. An empty or
+    /// invalid value disables the check.
+    fn is_time_reached(&self, setting: &str, now: &DateTime<Local>) -> bool {
+        let value = self.db.get_setting(setting).unwrap_or_default();
+        parse_time_of_day(&value).is_some_and(|time| now.time() >= time)
+    }
+
+    /// Keys sort chronologically, so a period before the last nudged one
+    /// (the clock moved backwards) counts as shown
+    fn has_shown_for_period(&self, kind: NudgeKind, level: GoalLevel, period_key: &str) -> bool {
+        self.db
+            .get_setting(&kind.setting_key(level))
+            .is_ok_and(|value| value.as_str() >= period_key)
+    }
+
+    fn mark_shown(&self, nudge: &PlanningNudge) -> Result<(), String> {
+        let key = nudge.kind.setting_key(nudge.level);
+        self.db
+            .set_setting(&key, &nudge.period_key)
+            .map_err(|e| format!("Failed to update {}: {}", key, e))
+    }
+}
+
+/// One notification listing every nudge with its counts
+fn notification(locale: &Locale, nudges: &[PlanningNudge], min_goals: usize) -> ReminderNotification {
+    let lines: Vec<String> = nudges
+        .iter()
+        .map(|nudge| {
+            let label = locale.t(&format!("reminder.levelGoals.{}", nudge.level.as_str()));
+            match nudge.kind {
+                NudgeKind::Unset => locale.t_with("reminder.planningUnset", &[
+                    ("label", label),
+                    ("count", nudge.goals.to_string()),
+                    ("min", min_goals.to_string()),
+                ]),
+                NudgeKind::Unfinished => locale.t_with("reminder.planningUnfinished", &[
+                    ("label", label),
+                    ("open", (nudge.goals - nudge.completed).to_string()),
+                    ("total", nudge.goals.to_string()),
+                ]),
+            }
+        })
+        .collect();
+
+    let title = if nudges.iter().all(|n| n.kind == NudgeKind::Unfinished) {
+        locale.t("reminder.wrapUpTitle")
+    } else {
+        locale.t("reminder.planningTitle")
+    };
+
+    ReminderNotification {
+        kind: ReminderKind::Planning,
+        title,
+        body: lines.join("\n"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Goal;
+    use crate::notifications::RecordingNotifier;
+    use chrono::TimeZone;
+
+    fn at(d: u32, h: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 12, d, h, 0, 0).unwrap()
+    }
+
+    fn reminder() -> (PlanningReminder, Arc<RecordingNotifier>) {
+        let db = Database::new(":memory:".into()).unwrap();
+        db.migrate().unwrap();
+        db.set_setting("language", "en").unwrap();
+        let notifier = Arc::new(RecordingNotifier::default());
+        let notifications = Arc::new(NotificationCenter::new(db.clone(), notifier.clone()));
+        (PlanningReminder::new(db, notifications), notifier)
+    }
+
+    fn add_goal(db: &Database, level: GoalLevel, period_start: DateTime<Local>, completed: bool) {
+        let mut goal = Goal::new(format!("{} goal", level.as_str()), level, period_start.timestamp_millis(), None);
+        goal.is_completed = completed;
+        db.add_goal(&goal).unwrap();
+    }
+
+    #[test]
+    fn test_unset_goals_are_nudged_once_per_period() {
+        let (reminder, notifier) = reminder();
+        // 2025-12-01 は月曜日（週・月の初日）
+        add_goal(&reminder.db, GoalLevel::Monthly, at(1, 0), false);
+        for _ in 0..3 {
+            add_goal(&reminder.db, GoalLevel::Weekly, at(1, 0), false);
+        }
+
+        // 設定時刻（09:00）前は何もしない
+        assert!(reminder.check_and_notify(at(1, 8).timestamp_millis()).unwrap().is_empty());
+
+        let nudges = reminder.check_and_notify(at(1, 9).timestamp_millis()).unwrap();
+        let levels: Vec<_> = nudges.iter().map(|n| (n.level, n.goals)).collect();
+        assert_eq!(levels, [(GoalLevel::Monthly, 1), (GoalLevel::Daily, 0)]);
+        assert_eq!(
+            notifier.sent.lock().unwrap()[0].body,
+            "This month's goals: 1 of 3 set\nToday's goals: 0 of 3 set"
+        );
+
+        // 同じ期間では再通知しないが、翌日は日次だけ再び対象になる
+        assert!(reminder.check_and_notify(at(1, 12).timestamp_millis()).unwrap().is_empty());
+        let nudges = reminder.check_and_notify(at(2, 9).timestamp_millis()).unwrap();
+        assert_eq!(nudges.len(), 1);
+        assert_eq!(nudges[0].period_key, "2025-12-02");
+    }
+
+    #[test]
+    fn test_unfinished_goals_are_nudged_on_last_day() {
+        let (reminder, notifier) = reminder();
+        reminder.db.set_setting("planning_min_goals", "1").unwrap();
+        // 2025-12-01 (月) から始まる週の最終日は 12-07 (日)
+        add_goal(&reminder.db, GoalLevel::Weekly, at(1, 0), true);
+        add_goal(&reminder.db, GoalLevel::Weekly, at(1, 0), false);
+        add_goal(&reminder.db, GoalLevel::Monthly, at(1, 0), false);
+        add_goal(&reminder.db, GoalLevel::Daily, at(6, 0), true);
+        add_goal(&reminder.db, GoalLevel::Daily, at(7, 0), true);
+
+        // 最終日でなければ未完了でも通知しない
+        assert!(reminder.check_and_notify(at(6, 18).timestamp_millis()).unwrap().is_empty());
+
+        let nudges = reminder.check_and_notify(at(7, 18).timestamp_millis()).unwrap();
+        assert_eq!(nudges.len(), 1);
+        assert_eq!((nudges[0].level, nudges[0].kind), (GoalLevel::Weekly, NudgeKind::Unfinished));
+
+        let sent = notifier.sent.lock().unwrap();
+        assert_eq!(sent[0].title, "Finish your goals");
+        assert_eq!(sent[0].body, "This week's goals: 1 of 2 still open");
+    }
+}
@@ -0,0 +1,382 @@
+// Spotlight-style quick add. One line such as "w: finalize Q4 plan #work
+// ^launch" is parsed into a level, a target period and a parent goal, shown
+// as a preview, and added through the normal add path.
+
+use std::sync::Mutex;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use crate::cli::QuickAdd;
+use crate::commands::goals::{create_goal, get_week_start_setting, period_has_room, MAX_GOALS_PER_PERIOD};
+use crate::commands::periods::{local_midnight, period_key, period_range};
+use crate::db::Database;
+use crate::i18n::Locale;
+use crate::models::{Goal, GoalLevel};
+
+/// What the quick-add field understood, before looking at existing goals
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedInput {
+    pub title: String,
+    pub level: GoalLevel,
+    /// A day inside the target period
+    pub date: NaiveDate,
+    /// Words after `^`, matched against parent goal titles first
+    pub parent_hints: Vec<String>,
+    /// Words after `#`, used for the parent when no `^` hint matches
+    pub tags: Vec<String>,
+}
+
+/// Why a quick-add line cannot be read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// The prefix names a day before today
+    PastDate(NaiveDate),
+    /// Nothing is left for the title after the prefix, tags and hints
+    MissingTitle,
+}
+
+impl ParseError {
+    pub fn message(&self, locale: &Locale) -> String {
+        match self {
+            ParseError::PastDate(date) => locale.t_with("quickAdd.pastDate", &[("date", date.to_string())]),
+            ParseError::MissingTitle => locale.t("quickAdd.missingTitle"),
+        }
+    }
+}
+
+/// Level and day of a prefix before ":" ("w", "tomorrow", "2026-10-20", ...)
+fn parse_prefix(prefix: &str, today: NaiveDate) -> Option<(GoalLevel, NaiveDate)> {
+    let prefix = prefix.trim().to_lowercase();
+    let next_month = NaiveDate::from_ymd_opt(today.year(), today.month(), 1)
+        .and_then(|first| first.checked_add_months(chrono::Months::new(1)))?;
+
+    let parsed = match prefix.as_str() {
+        "d" | "day" | "daily" | "today" | "今日" => (GoalLevel::Daily, today),
+        "t" | "tmr" | "tomorrow" | "明日" => (GoalLevel::Daily, today + Duration::days(1)),
+        "w" | "week" | "weekly" | "今週" => (GoalLevel::Weekly, today),
+        "nw" | "next week" | "来週" => (GoalLevel::Weekly, today + Duration::days(7)),
+        "m" | "month" | "monthly" | "今月" => (GoalLevel::Monthly, today),
+        "nm" | "next month" | "来月" => (GoalLevel::Monthly, next_month),
+        _ => (GoalLevel::Daily, NaiveDate::parse_from_str(&prefix, "%Y-%m-%d").ok()?),
+    };
+    Some(parsed)
+}
+
+/// Parse a quick-add line. Without a known prefix the whole line is a goal
+/// for today, so titles like "Note: ..." are kept as typed.
+pub fn parse_input(input: &str, today: NaiveDate) -> Result<ParsedInput, ParseError> {
+    let input = input.trim();
+
+    // 1. 「w:」「tomorrow:」などの接頭辞（全角コロンも可）
+    let (level, date, rest) = input
+        .split_once([':', '：'])
+        .and_then(|(prefix, rest)| parse_prefix(prefix, today).map(|(level, date)| (level, date, rest)))
+        .unwrap_or((GoalLevel::Daily, today, input));
+
+    if date < today {
+        return Err(ParseError::PastDate(date));
+    }
+
+    // 2. #タグ と ^親目標 を取り出し、残りをタイトルにする
+    let mut words = Vec::new();
+    let mut parent_hints = Vec::new();
+    let mut tags = Vec::new();
+    for word in rest.split_whitespace() {
+        match (word.strip_prefix('^'), word.strip_prefix('#')) {
+            (Some(hint), _) if !hint.is_empty() => parent_hints.push(hint.to_string()),
+            (_, Some(tag)) if !tag.is_empty() => tags.push(tag.to_string()),
+            _ => words.push(word),
+        }
+    }
+
+    let title = words.join(" ");
+    if title.is_empty() {
+        return Err(ParseError::MissingTitle);
+    }
+
+    Ok(ParsedInput { title, level, date, parent_hints, tags })
+}
+
+/// How well `query` matches `title`: 3 for the whole title, 2 for a part of
+/// it, 1 when its letters appear in order ("q4pl" in "Q4 plan").
+fn match_score(query: &str, title: &str) -> Option<u8> {
+    let query = query.to_lowercase().replace(['-', '_'], " ");
+    let title = title.to_lowercase();
+    if title == query {
+        return Some(3);
+    }
+    if title.contains(&query) {
+        return Some(2);
+    }
+    let mut letters = title.chars();
+    query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .all(|c| letters.any(|t| t == c))
+        .then_some(1)
+}
+
+/// Best match for any of `queries`; the oldest goal wins a tie
+fn best_match<'a>(queries: &[String], candidates: &'a [Goal]) -> Option<&'a Goal> {
+    let mut best: Option<(u8, &Goal)> = None;
+    for goal in candidates {
+        let score = queries.iter().filter_map(|q| match_score(q, &goal.title)).max();
+        if let Some(score) = score {
+            if best.is_none_or(|(best_score, _)| score > best_score) {
+                best = Some((score, goal));
+            }
+        }
+    }
+    best.map(|(_, goal)| goal)
+}
+
+/// Parent for the parsed hints among `candidates` (goals one level up in the
+/// target period). `^` hints are tried before `#` tags.
+pub fn find_parent<'a>(parsed: &ParsedInput, candidates: &'a [Goal]) -> Option<&'a Goal> {
+    best_match(&parsed.parent_hints, candidates).or_else(|| best_match(&parsed.tags, candidates))
+}
+
+/// What adding the line would do, shown while typing
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickAddPreview {
+    pub title: String,
+    pub level: GoalLevel,
+    /// Timestamp passed to add_goal: now for the current period, otherwise
+    /// midnight of the target day
+    pub period_start: i64,
+    pub period_key: String,
+    pub parent: Option<Goal>,
+    pub tags: Vec<String>,
+    /// `^` hints that matched no goal
+    pub unmatched_hints: Vec<String>,
+    /// Why the goal cannot be added, e.g. the period already has 3 goals
+    pub error: Option<String>,
+}
+
+/// Resolve a parsed line against the database
+pub fn preview_parsed(db: &Database, parsed: ParsedInput, now: DateTime<Local>) -> QuickAddPreview {
+    let week_start = get_week_start_setting(db);
+    let at = if parsed.date == now.date_naive() { now } else { local_midnight(parsed.date) };
+
+    let parent_level = match parsed.level {
+        GoalLevel::Daily => Some(GoalLevel::Weekly),
+        GoalLevel::Weekly => Some(GoalLevel::Monthly),
+        GoalLevel::Monthly => None,
+    };
+    let candidates = parent_level
+        .map(|level| {
+            let (start, end) = period_range(level, &at, week_start);
+            db.get_goals_in_range(level.as_str(), start.timestamp_millis(), end.timestamp_millis())
+                .unwrap_or_default()
+        })
+        .unwrap_or_default();
+    let parent = find_parent(&parsed, &candidates).cloned();
+
+    let unmatched_hints = parsed
+        .parent_hints
+        .iter()
+        .filter(|hint| best_match(std::slice::from_ref(*hint), &candidates).is_none())
+        .cloned()
+        .collect();
+
+    QuickAddPreview {
+        period_key: period_key(parsed.level, at.timestamp_millis(), week_start).unwrap_or_default(),
+        error: match period_has_room(db, parsed.level, &at) {
+            Ok(true) => None,
+            Ok(false) => Some(Locale::for_db(db).t_with(
+                "quickAdd.periodFull",
+                &[("max", MAX_GOALS_PER_PERIOD.to_string())],
+            )),
+            Err(e) => Some(e),
+        },
+        title: parsed.title,
+        level: parsed.level,
+        period_start: at.timestamp_millis(),
+        parent,
+        tags: parsed.tags,
+        unmatched_hints,
+    }
+}
+
+pub fn preview<R: Runtime>(app: &AppHandle<R>, input: &str) -> Result<QuickAddPreview, String> {
+    let now = Local::now();
+    let db = app.state::<Database>();
+    let parsed = parse_input(input, now.date_naive()).map_err(|e| e.message(&Locale::for_db(&db)))?;
+    Ok(preview_parsed(&db, parsed, now))
+}
+
+/// Add the goal described by `input`. The line is parsed again so the goal
+/// always matches what the preview of the same text showed.
+pub fn commit<R: Runtime>(app: &AppHandle<R>, input: &str) -> Result<Goal, String> {
+    add_previewed(app, preview(app, input)?)
+}
+
+/// A goal from a link, as if typed for the current period. The title is
+/// taken as it is, so `#` and `^` in it stay part of the title.
+pub fn parsed_goal(goal: &QuickAdd, today: NaiveDate) -> Result<ParsedInput, ParseError> {
+    let title = goal.title.trim();
+    if title.is_empty() {
+        return Err(ParseError::MissingTitle);
+    }
+    Ok(ParsedInput {
+        title: title.to_string(),
+        level: goal.level,
+        date: today,
+        parent_hints: Vec::new(),
+        tags: Vec::new(),
+    })
+}
+
+pub fn preview_goal<R: Runtime>(app: &AppHandle<R>, goal: &QuickAdd) -> Result<QuickAddPreview, String> {
+    let now = Local::now();
+    let db = app.state::<Database>();
+    let parsed = parsed_goal(goal, now.date_naive()).map_err(|e| e.message(&Locale::for_db(&db)))?;
+    Ok(preview_parsed(&db, parsed, now))
+}
+
+/// Add a goal from a link, resolved the same way as its preview
+pub fn commit_goal<R: Runtime>(app: &AppHandle<R>, goal: &QuickAdd) -> Result<Goal, String> {
+    add_previewed(app, preview_goal(app, goal)?)
+}
+
+fn add_previewed<R: Runtime>(app: &AppHandle<R>, preview: QuickAddPreview) -> Result<Goal, String> {
+    if let Some(error) = preview.error {
+        return Err(error);
+    }
+    create_goal(
+        app,
+        preview.title,
+        preview.level,
+        preview.period_start,
+        preview.parent.map(|parent| parent.id),
+    )
+}
+
+/// Goal the quick-add window starts with, kept until the window takes it
+/// since a link can start the app before the window has loaded
+#[derive(Default)]
+pub struct PendingGoal(Mutex<Option<QuickAdd>>);
+
+impl PendingGoal {
+    pub fn take(&self) -> Option<QuickAdd> {
+        self.0.lock().unwrap().take()
+    }
+}
+
+/// Show the quick-add window with `goal` filled in, so the user sees the
+/// preview and confirms the goal
+pub fn show_with_goal<R: Runtime>(app: &AppHandle<R>, goal: QuickAdd) {
+    *app.state::<PendingGoal>().0.lock().unwrap() = Some(goal);
+    show(app);
+}
+
+/// Show the quick-add window in the middle of the screen, ready for typing
+pub fn show<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("quick-add") {
+        let _ = window.center();
+        let _ = window.show();
+        let _ = window.set_focus();
+        let _ = window.emit_to("quick-add", "quick-add-opened", ());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn goal(title: &str) -> Goal {
+        Goal::new(title.to_string(), GoalLevel::Weekly, 0, None)
+    }
+
+    #[test]
+    fn test_parse_input() {
+        let today = date(2026, 10, 18);
+
+        let parsed = parse_input("w: finalize Q4 plan #work ^launch", today).unwrap();
+        assert_eq!(parsed.title, "finalize Q4 plan");
+        assert_eq!(parsed.level, GoalLevel::Weekly);
+        assert_eq!(parsed.date, today);
+        assert_eq!(parsed.parent_hints, ["launch"]);
+        assert_eq!(parsed.tags, ["work"]);
+
+        let parsed = parse_input("tomorrow: call bank", today).unwrap();
+        assert_eq!((parsed.level, parsed.date), (GoalLevel::Daily, date(2026, 10, 19)));
+        let parsed = parse_input("明日：銀行に電話", today).unwrap();
+        assert_eq!((parsed.title.as_str(), parsed.date), ("銀行に電話", date(2026, 10, 19)));
+
+        assert_eq!(parse_input("m: run 50km", today).unwrap().level, GoalLevel::Monthly);
+        assert_eq!(parse_input("nm: plan trip", today).unwrap().date, date(2026, 11, 1));
+        assert_eq!(parse_input("2026-10-20: dentist", today).unwrap().date, date(2026, 10, 20));
+    }
+
+    #[test]
+    fn test_parse_input_without_prefix() {
+        let today = date(2026, 10, 18);
+
+        // 知らない接頭辞はタイトルの一部として残す
+        let parsed = parse_input("Note: C# basics", today).unwrap();
+        assert_eq!(parsed.title, "Note: C# basics");
+        assert_eq!((parsed.level, parsed.date), (GoalLevel::Daily, today));
+
+        assert_eq!(parse_input("w: #work ^launch", today), Err(ParseError::MissingTitle));
+        assert_eq!(parse_input("   ", today), Err(ParseError::MissingTitle));
+        assert_eq!(parse_input("2026-10-01: too late", today), Err(ParseError::PastDate(date(2026, 10, 1))));
+    }
+
+    #[test]
+    fn test_messages_are_localized() {
+        let en = Locale::from_setting("en");
+        let ja = Locale::from_setting("ja");
+        assert_eq!(ParseError::PastDate(date(2026, 10, 1)).message(&en), "2026-10-01 is in the past");
+        assert_eq!(ParseError::MissingTitle.message(&ja), "目標のタイトルを入力してください");
+
+        let db = Database::new(":memory:".into()).unwrap();
+        db.migrate().unwrap();
+        db.set_setting("language", "ja").unwrap();
+        let now = Local::now();
+        for i in 0..3 {
+            db.add_goal(&Goal::new(format!("goal {i}"), GoalLevel::Daily, now.timestamp_millis(), None)).unwrap();
+        }
+        let parsed = parse_input("one more", now.date_naive()).unwrap();
+        assert_eq!(preview_parsed(&db, parsed, now).error.as_deref(), Some("この期間の目標はすでに3つあります"));
+    }
+
+    #[test]
+    fn test_parsed_goal() {
+        let today = date(2026, 10, 18);
+        let link = |title: &str| QuickAdd { title: title.to_string(), level: GoalLevel::Weekly };
+
+        // リンクのタイトルは # や ^ を含めてそのまま使う
+        let parsed = parsed_goal(&link(" Fix #123 ^now "), today).unwrap();
+        assert_eq!((parsed.title.as_str(), parsed.level, parsed.date), ("Fix #123 ^now", GoalLevel::Weekly, today));
+        assert!(parsed.tags.is_empty() && parsed.parent_hints.is_empty());
+        assert_eq!(parsed_goal(&link("  "), today), Err(ParseError::MissingTitle));
+
+        let db = Database::new(":memory:".into()).unwrap();
+        db.migrate().unwrap();
+        let now = Local::now();
+        let preview = preview_parsed(&db, parsed_goal(&link("Fix #123"), now.date_naive()).unwrap(), now);
+        assert_eq!((preview.title.as_str(), preview.error), ("Fix #123", None));
+    }
+
+    #[test]
+    fn test_find_parent() {
+        let candidates = [goal("Ship the launch page"), goal("Work out 3x"), goal("Launch")];
+        let parsed = |input: &str| parse_input(input, date(2026, 10, 18)).unwrap();
+
+        // 完全一致 > 部分一致 > 文字の並び
+        assert_eq!(find_parent(&parsed("x ^launch"), &candidates).unwrap().title, "Launch");
+        assert_eq!(find_parent(&parsed("x ^page"), &candidates).unwrap().title, "Ship the launch page");
+        assert_eq!(find_parent(&parsed("x ^wo3x"), &candidates).unwrap().title, "Work out 3x");
+        assert_eq!(find_parent(&parsed("x ^launch-page"), &candidates).unwrap().title, "Ship the launch page");
+
+        // ^ が当たらなければ #タグで探す
+        assert_eq!(find_parent(&parsed("x #work ^nothing"), &candidates).unwrap().title, "Work out 3x");
+        assert!(find_parent(&parsed("x #zzz"), &candidates).is_none());
+    }
+}
@@ -1,8 +1,11 @@
-pub mod background;
-
+use std::sync::Arc;
 use crate::db::Database;
-use chrono::{DateTime, Datelike, Local};
-use tauri::{AppHandle, Emitter, Manager};
+use crate::i18n::Locale;
+use crate::commands::periods::{parse_time_of_day, period_key, period_range};
+use crate::models::{GoalLevel, ReviewReport};
+use crate::notifications::{NotificationCenter, ReminderNotification};
+use chrono::{DateTime, Duration, Local};
+use tauri::{AppHandle, Emitter};
 
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct PeriodChangeEvent {
@@ -10,82 +13,133 @@ pub struct PeriodChangeEvent {
     pub has_monthly_change: bool,
     pub current_week_key: String,
     pub current_month_key: String,
+    /// The end-of-day shutdown review for `current_day_key` is due
+    pub has_daily_review: bool,
+    pub current_day_key: String,
+    /// Review of the week that just ended, when `has_weekly_change`
+    pub weekly_review: Option<ReviewReport>,
+    /// Review of the month that just ended, when `has_monthly_change`
+    pub monthly_review: Option<ReviewReport>,
 }
 
 pub struct ReflectionReminder {
     db: Database,
+    notifications: Arc<NotificationCenter>,
 }
 
 impl ReflectionReminder {
-    pub fn new(db: Database) -> Self {
-        Self { db }
+    pub fn new(db: Database, notifications: Arc<NotificationCenter>) -> Self {
+        Self { db, notifications }
     }
 
     /// 期間変更をチェックし、必要に応じてイベントを発行
-    pub fn check_and_notify(&self, app: &AppHandle) -> Result<(), String> {
+    /// `last_check` は前回チェック時刻（未実行なら 0）、`now` は現在時刻（ms）
+    pub fn check_and_notify(&self, app: &AppHandle, last_check: i64, now: i64) -> Result<(), String> {
         // 1. 設定チェック
         let enabled = self.is_enabled()?;
         if !enabled {
             return Ok(());
         }
 
-        // 2. 最終チェック日時を取得
-        let last_check = self.get_last_check_timestamp()?;
-        let now = chrono::Utc::now().timestamp_millis();
-
-        // 3. 週開始日を取得
+        // 2. 週開始日を取得
         let week_start = self.get_week_start()?;
 
-        // 4. 期間変更を検出
+        // 3. 期間変更を検出
         let (has_weekly, has_monthly) = self.detect_period_change(last_check, now, week_start)?;
 
-        // 5. 表示履歴チェック（同じ期間で既に表示済みなら無視）
-        if has_weekly || has_monthly {
-            let current_week_key = self.get_current_week_key(now, week_start)?;
-            let current_month_key = self.get_current_month_key(now)?;
-
-            let already_shown_weekly = self.has_shown_for_period("weekly", &current_week_key)?;
-            let already_shown_monthly = self.has_shown_for_period("monthly", &current_month_key)?;
-
-            let should_show_weekly = has_weekly && !already_shown_weekly;
-            let should_show_monthly = has_monthly && !already_shown_monthly;
-
-            if should_show_weekly || should_show_monthly {
-                // 6. イベント発行
-                let event = PeriodChangeEvent {
-                    has_weekly_change: should_show_weekly,
-                    has_monthly_change: should_show_monthly,
-                    current_week_key: current_week_key.clone(),
-                    current_month_key: current_month_key.clone(),
-                };
-
-                println!("[ReflectionReminder] Emitting reflection-prompt-trigger event: {:?}", event);
-
-                app.emit("reflection-prompt-trigger", event)
-                    .map_err(|e| format!("Failed to emit event: {}", e))?;
-
-                // ポップオーバーウィンドウを表示
-                if let Some(window) = app.get_webview_window("popover") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
-
-                // 7. 表示履歴を更新
-                if should_show_weekly {
-                    self.mark_shown("weekly", &current_week_key)?;
-                }
-                if should_show_monthly {
-                    self.mark_shown("monthly", &current_month_key)?;
-                }
+        // 4. 表示履歴チェック（同じ期間で既に表示済みなら無視）
+        let current_key = |level| period_key(level, now, week_start).ok_or("Invalid timestamp");
+        let current_week_key = current_key(GoalLevel::Weekly)?;
+        let current_month_key = current_key(GoalLevel::Monthly)?;
+        let current_day_key = current_key(GoalLevel::Daily)?;
+
+        let should_show_weekly = has_weekly && !self.has_shown_for_period("weekly", &current_week_key)?;
+        let should_show_monthly = has_monthly && !self.has_shown_for_period("monthly", &current_month_key)?;
+
+        // 一日の終わりの振り返り（設定時刻を過ぎたら当日分を一度だけ）
+        let should_show_daily = self.is_daily_review_due(now)?
+            && !self.has_shown_for_period("daily", &current_day_key)?;
+
+        if should_show_weekly || should_show_monthly || should_show_daily {
+            // 5. 終了した期間のレビューを作成してイベント発行
+            let event = PeriodChangeEvent {
+                has_weekly_change: should_show_weekly,
+                has_monthly_change: should_show_monthly,
+                has_daily_review: should_show_daily,
+                current_week_key: current_week_key.clone(),
+                current_month_key: current_month_key.clone(),
+                current_day_key: current_day_key.clone(),
+                weekly_review: should_show_weekly
+                    .then(|| self.prepare_review(app, GoalLevel::Weekly, now, week_start))
+                    .flatten(),
+                monthly_review: should_show_monthly
+                    .then(|| self.prepare_review(app, GoalLevel::Monthly, now, week_start))
+                    .flatten(),
+            };
+
+            println!("[ReflectionReminder] Emitting reflection-prompt-trigger event: {:?}", event);
+
+            app.emit("reflection-prompt-trigger", event)
+                .map_err(|e| format!("Failed to emit event: {}", e))?;
+
+            // 通知を表示してポップオーバーを開く（保留中なら保留明けに）
+            let locale = Locale::for_db(&self.db);
+            let notification = ReminderNotification::reflection(
+                &locale,
+                should_show_weekly,
+                should_show_monthly,
+                should_show_daily,
+            );
+            if let Err(e) = self.notifications.deliver(&notification, now) {
+                eprintln!("[ReflectionReminder] Failed to show notification: {}", e);
             }
-        }
 
-        // 8. 最終チェック日時を更新
-        self.update_last_check_timestamp(now)?;
+            // 6. 表示履歴を更新
+            if should_show_weekly {
+                self.mark_shown("weekly", &current_week_key)?;
+            }
+            if should_show_monthly {
+                self.mark_shown("monthly", &current_month_key)?;
+            }
+            if should_show_daily {
+                self.mark_shown("daily", &current_day_key)?;
+            }
+        }
 
         Ok(())
     }
 
+    /// Build the review of the period that ended before `now` and save it to
+    /// the report archive. Failures are logged so they never block the prompt.
+    fn prepare_review(
+        &self,
+        app: &AppHandle,
+        level: GoalLevel,
+        now: i64,
+        week_start: i32,
+    ) -> Option<ReviewReport> {
+        let now_dt = DateTime::from_timestamp_millis(now)?.with_timezone(&Local);
+        let (current_start, _) = period_range(level, &now_dt, week_start);
+        let closing_dt = current_start - Duration::milliseconds(1);
+
+        let report = match crate::review::build_review(&self.db, level, &closing_dt, &now_dt, week_start) {
+            Ok(report) => report,
+            Err(e) => {
+                eprintln!("[ReflectionReminder] Failed to build {} review: {}", level.as_str(), e);
+                return None;
+            }
+        };
+
+        match crate::review::reports_dir(app)
+            .and_then(|dir| crate::review::save_review(&report, &Locale::for_db(&self.db), &dir))
+        {
+            Ok(path) => println!("[ReflectionReminder] Saved review report to {}", path.display()),
+            Err(e) => eprintln!("[ReflectionReminder] Failed to save review report: {}", e),
+        }
+
+        Some(report)
+    }
+
     fn is_enabled(&self) -> Result<bool, String> {
         let conn = self.db.conn.lock().unwrap();
         let value: String = conn
@@ -99,32 +153,6 @@ impl ReflectionReminder {
         Ok(value == "true")
     }
 
-    fn get_last_check_timestamp(&self) -> Result<i64, String> {
-        let conn = self.db.conn.lock().unwrap();
-        let value: String = conn
-            .query_row(
-                "SELECT value FROM settings WHERE key = ?",
-                ["last_period_check_timestamp"],
-                |row| row.get(0),
-            )
-            .map_err(|e| format!("Failed to get last_period_check_timestamp: {}", e))?;
-
-        value
-            .parse()
-            .map_err(|e| format!("Failed to parse last_period_check_timestamp: {}", e))
-    }
-
-    fn update_last_check_timestamp(&self, timestamp: i64) -> Result<(), String> {
-        let conn = self.db.conn.lock().unwrap();
-        conn.execute(
-            "UPDATE settings SET value = ? WHERE key = ?",
-            [timestamp.to_string(), "last_period_check_timestamp".to_string()],
-        )
-        .map_err(|e| format!("Failed to update last_period_check_timestamp: {}", e))?;
-
-        Ok(())
-    }
-
     fn get_week_start(&self) -> Result<i32, String> {
         let conn = self.db.conn.lock().unwrap();
         let value: String = conn
@@ -146,8 +174,9 @@ impl ReflectionReminder {
         now: i64,
         week_start: i32,
     ) -> Result<(bool, bool), String> {
-        // If this is the first check (last_check == 0), don't trigger
-        if last_check == 0 {
+        // If this is the first check (last_check == 0), don't trigger.
+        // A clock moved backwards never starts a new period either.
+        if last_check == 0 || now < last_check {
             return Ok((false, false));
         }
 
@@ -159,59 +188,31 @@ impl ReflectionReminder {
             .ok_or("Invalid now timestamp")?
             .with_timezone(&Local);
 
-        // Check if week changed
-        let has_weekly_change = !self.is_same_week(&last_dt, &now_dt, week_start);
-
-        // Check if month changed
-        let has_monthly_change = !self.is_same_month(&last_dt, &now_dt);
+        // 週・月の境界は目標と同じ period_range で判定する
+        let changed = |level| {
+            period_range(level, &last_dt, week_start).0 != period_range(level, &now_dt, week_start).0
+        };
+        let has_weekly_change = changed(GoalLevel::Weekly);
+        let has_monthly_change = changed(GoalLevel::Monthly);
 
         Ok((has_weekly_change, has_monthly_change))
     }
 
-    fn is_same_week(&self, dt1: &DateTime<Local>, dt2: &DateTime<Local>, week_start: i32) -> bool {
-        let week1_start = self.get_week_start_dt(dt1, week_start);
-        let week2_start = self.get_week_start_dt(dt2, week_start);
-        self.is_same_day(&week1_start, &week2_start)
-    }
-
-    fn is_same_day(&self, dt1: &DateTime<Local>, dt2: &DateTime<Local>) -> bool {
-        dt1.year() == dt2.year() && dt1.ordinal() == dt2.ordinal()
-    }
-
-    fn is_same_month(&self, dt1: &DateTime<Local>, dt2: &DateTime<Local>) -> bool {
-        dt1.year() == dt2.year() && dt1.month() == dt2.month()
-    }
-
-    fn get_week_start_dt(&self, dt: &DateTime<Local>, week_start: i32) -> DateTime<Local> {
-        let current_weekday = dt.weekday().num_days_from_sunday() as i32;
-        let target_weekday = (week_start - 1) % 7;
-        let days_diff = (current_weekday - target_weekday + 7) % 7;
-        let week_start_date = dt.date_naive() - chrono::Duration::days(days_diff as i64);
-
-        week_start_date
-            .and_hms_opt(0, 0, 0)
-            .unwrap()
-            .and_local_timezone(Local)
-            .unwrap()
-    }
-
-    fn get_current_week_key(&self, timestamp: i64, week_start: i32) -> Result<String, String> {
-        let dt = DateTime::from_timestamp_millis(timestamp)
-            .ok_or("Invalid timestamp")?
-            .with_timezone(&Local);
-
-        let week_start_dt = self.get_week_start_dt(&dt, week_start);
-        let iso_week = week_start_dt.iso_week();
-
-        Ok(format!("{}-W{:02}", iso_week.year(), iso_week.week()))
-    }
+    /// Whether the configured end-of-day prompt time has passed today
+    fn is_daily_review_due(&self, timestamp: i64) -> Result<bool, String> {
+        let value = self
+            .db
+            .get_setting("daily_reflection_time")
+            .unwrap_or_default();
+        let Some(prompt_time) = parse_time_of_day(&value) else {
+            return Ok(false);
+        };
 
-    fn get_current_month_key(&self, timestamp: i64) -> Result<String, String> {
         let dt = DateTime::from_timestamp_millis(timestamp)
             .ok_or("Invalid timestamp")?
             .with_timezone(&Local);
 
-        Ok(format!("{}-{:02}", dt.year(), dt.month()))
+        Ok(dt.time() >= prompt_time)
     }
 
     fn has_shown_for_period(&self, level: &str, period_key: &str) -> Result<bool, String> {
@@ -219,6 +220,7 @@ impl ReflectionReminder {
         let key = match level {
             "weekly" => "last_weekly_reflection_prompt",
             "monthly" => "last_monthly_reflection_prompt",
+            "daily" => "last_daily_reflection_prompt",
             _ => return Err(format!("Invalid level: {}", level)),
         };
 
@@ -228,7 +230,8 @@ impl ReflectionReminder {
             })
             .map_err(|e| format!("Failed to get {}: {}", key, e))?;
 
-        Ok(value == period_key)
+        // キーは時系列順にソートできるので、時計が戻って過去の期間に入っても再表示しない
+        Ok(value.as_str() >= period_key)
     }
 
     fn mark_shown(&self, level: &str, period_key: &str) -> Result<(), String> {
@@ -236,6 +239,7 @@ impl ReflectionReminder {
         let key = match level {
             "weekly" => "last_weekly_reflection_prompt",
             "monthly" => "last_monthly_reflection_prompt",
+            "daily" => "last_daily_reflection_prompt",
             _ => return Err(format!("Invalid level: {}", level)),
         };
 
@@ -248,3 +252,38 @@ impl ReflectionReminder {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::notifications::RecordingNotifier;
+
+    fn reminder() -> ReflectionReminder {
+        let db = Database::new(":memory:".into()).unwrap();
+        db.migrate().unwrap();
+        let notifications = NotificationCenter::new(db.clone(), Arc::new(RecordingNotifier::default()));
+        ReflectionReminder::new(db, Arc::new(notifications))
+    }
+
+    fn at(m: u32, d: u32, h: u32) -> i64 {
+        Local.with_ymd_and_hms(2025, m, d, h, 0, 0).unwrap().timestamp_millis()
+    }
+
+    #[test]
+    fn test_detect_period_change_follows_period_range() {
+        let reminder = reminder();
+        // 2025-12-06 は土曜、12-07 は日曜、12-08 は月曜
+        assert_eq!(reminder.detect_period_change(at(12, 6, 12), at(12, 7, 12), 2), Ok((false, false)));
+        assert_eq!(reminder.detect_period_change(at(12, 7, 23), at(12, 8, 0), 2), Ok((true, false)));
+        // 日曜始まりなら土曜から日曜で週が変わる
+        assert_eq!(reminder.detect_period_change(at(12, 6, 12), at(12, 7, 12), 1), Ok((true, false)));
+        // 2025-11-30 は日曜: 日曜始まりでは同じ週のまま月だけ変わる
+        assert_eq!(reminder.detect_period_change(at(11, 30, 12), at(12, 1, 12), 1), Ok((false, true)));
+        assert_eq!(reminder.detect_period_change(at(11, 30, 12), at(12, 1, 12), 2), Ok((true, true)));
+
+        // 初回と時計の巻き戻しでは何も起きない
+        assert_eq!(reminder.detect_period_change(0, at(12, 8, 0), 2), Ok((false, false)));
+        assert_eq!(reminder.detect_period_change(at(12, 8, 0), at(12, 1, 0), 2), Ok((false, false)));
+    }
+}
@@ -0,0 +1,308 @@
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Duration, Local};
+use tauri::{AppHandle, Runtime};
+use crate::commands::periods::{period_key, period_range};
+use crate::db::Database;
+use crate::i18n::Locale;
+use crate::models::{Goal, GoalLevel, LevelStreak, Reflection, ReviewGoal, ReviewReport};
+
+/// Build the review of the period of `level` containing `period_dt`.
+/// `now` decides which period the streak is measured against.
+pub fn build_review(
+    db: &Database,
+    level: GoalLevel,
+    period_dt: &DateTime<Local>,
+    now: &DateTime<Local>,
+    week_start: i32,
+) -> anyhow::Result<ReviewReport> {
+    let (start, end) = period_range(level, period_dt, week_start);
+    let (from, to) = (start.timestamp_millis(), end.timestamp_millis());
+
+    let goals = db.get_goals_in_range(level.as_str(), from, to)?;
+    let children = match level {
+        GoalLevel::Monthly => db.get_goals_in_range(GoalLevel::Weekly.as_str(), from, to)?,
+        GoalLevel::Weekly => db.get_goals_in_range(GoalLevel::Daily.as_str(), from, to)?,
+        GoalLevel::Daily => Vec::new(),
+    };
+
+    let summaries = db.period_summaries(level, week_start)?;
+    let (current_start, _) = period_range(level, now, week_start);
+    let (current, longest) =
+        crate::stats::streaks_for(level, &summaries, current_start.date_naive());
+
+    let (previous_start, _) = period_range(level, &(start - Duration::milliseconds(1)), week_start);
+    let previous_reflection = match period_key(level, previous_start.timestamp_millis(), week_start) {
+        Some(key) => db.get_reflection(level.as_str(), &key)?,
+        None => None,
+    };
+
+    let carry_overs = goals.iter().filter(|g| !g.is_completed).cloned().collect();
+    let completed = goals.iter().filter(|g| g.is_completed).count() as u32;
+    let goals = goals
+        .into_iter()
+        .map(|goal| {
+            let linked: Vec<Goal> = children
+                .iter()
+                .filter(|c| c.parent_goal_id.as_deref() == Some(goal.id.as_str()))
+                .cloned()
+                .collect();
+            ReviewGoal {
+                children_completed: linked.iter().filter(|c| c.is_completed).count() as u32,
+                children: linked,
+                goal,
+            }
+        })
+        .collect();
+
+    Ok(ReviewReport {
+        level,
+        period_key: period_key(level, from, week_start).unwrap_or_default(),
+        period_start: from,
+        period_end: to,
+        generated_at: now.timestamp_millis(),
+        goals,
+        completed,
+        carry_overs,
+        streak: LevelStreak { level, current, longest },
+        previous_reflection,
+    })
+}
+
+fn title(report: &ReviewReport, locale: &Locale) -> String {
+    locale.t_with(
+        &format!("report.title.{}", report.level.as_str()),
+        &[("period", report.period_key.clone())],
+    )
+}
+
+fn goals_heading(report: &ReviewReport, locale: &Locale) -> String {
+    locale.t_with("report.goals", &[
+        ("completed", report.completed.to_string()),
+        ("total", report.goals.len().to_string()),
+    ])
+}
+
+fn streak_line(report: &ReviewReport, locale: &Locale) -> String {
+    locale.t_with("report.streakValue", &[
+        ("current", report.streak.current.to_string()),
+        ("longest", report.streak.longest.to_string()),
+    ])
+}
+
+fn insights(reflection: &Reflection) -> Vec<&str> {
+    [&reflection.insight_1, &reflection.insight_2, &reflection.insight_3]
+        .into_iter()
+        .filter_map(|insight| insight.as_deref())
+        .filter(|insight| !insight.trim().is_empty())
+        .collect()
+}
+
+fn format_date(timestamp: i64) -> String {
+    DateTime::from_timestamp_millis(timestamp)
+        .map(|dt| dt.with_timezone(&Local).format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// Inclusive date range of the report, e.g. "2025-12-01 – 2025-12-07"
+fn date_range(report: &ReviewReport) -> String {
+    format!(
+        "{} – {}",
+        format_date(report.period_start),
+        format_date(report.period_end - 1)
+    )
+}
+
+/// Render the report as Markdown in the language of `locale`
+pub fn render_markdown(report: &ReviewReport, locale: &Locale) -> String {
+    let mut md = format!(
+        "# {}\n\n{}\n\n## {}\n\n",
+        title(report, locale),
+        date_range(report),
+        goals_heading(report, locale)
+    );
+
+    if report.goals.is_empty() {
+        md.push_str(&format!("_{}_\n", locale.t("report.noGoals")));
+    }
+    for item in &report.goals {
+        let mark = if item.goal.is_completed { "x" } else { " " };
+        md.push_str(&format!("- [{}] {}", mark, item.goal.title));
+        if !item.children.is_empty() {
+            md.push_str(&format!(" ({}/{})", item.children_completed, item.children.len()));
+        }
+        md.push('\n');
+        for child in &item.children {
+            let mark = if child.is_completed { "x" } else { " " };
+            md.push_str(&format!("  - [{}] {}\n", mark, child.title));
+        }
+    }
+
+    md.push_str(&format!("\n## {}\n\n", locale.t("report.carryOvers")));
+    if report.carry_overs.is_empty() {
+        md.push_str(&format!("_{}_\n", locale.t("report.allFinished")));
+    }
+    for goal in &report.carry_overs {
+        md.push_str(&format!("- {}\n", goal.title));
+    }
+
+    md.push_str(&format!(
+        "\n## {}\n\n{}\n",
+        locale.t("report.streak"),
+        streak_line(report, locale)
+    ));
+
+    if let Some(reflection) = &report.previous_reflection {
+        md.push_str(&format!(
+            "\n## {}\n\n",
+            locale.t_with("report.insightsFrom", &[("period", reflection.period_key.clone())])
+        ));
+        for insight in insights(reflection) {
+            md.push_str(&format!("- {}\n", insight));
+        }
+    }
+
+    md
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn html_goal(goal: &Goal) -> String {
+    format!(
+        "<span class=\"{}\">{} {}</span>",
+        if goal.is_completed { "done" } else { "open" },
+        if goal.is_completed { "☑" } else { "☐" },
+        escape_html(&goal.title)
+    )
+}
+
+/// Render the report as a self-contained HTML page in the language of `locale`
+pub fn render_html(report: &ReviewReport, locale: &Locale) -> String {
+    let title = title(report, locale);
+    let mut body = format!(
+        "<h1>{}</h1>\n<p>{}</p>\n<h2>{}</h2>\n<ul>\n",
+        escape_html(&title),
+        date_range(report),
+        escape_html(&goals_heading(report, locale))
+    );
+
+    for item in &report.goals {
+        body.push_str(&format!("<li>{}", html_goal(&item.goal)));
+        if !item.children.is_empty() {
+            body.push_str(&format!(
+                " ({}/{})\n<ul>\n",
+                item.children_completed,
+                item.children.len()
+            ));
+            for child in &item.children {
+                body.push_str(&format!("<li>{}</li>\n", html_goal(child)));
+            }
+            body.push_str("</ul>\n");
+        }
+        body.push_str("</li>\n");
+    }
+    body.push_str(&format!("</ul>\n<h2>{}</h2>\n<ul>\n", escape_html(&locale.t("report.carryOvers"))));
+    for goal in &report.carry_overs {
+        body.push_str(&format!("<li>{}</li>\n", escape_html(&goal.title)));
+    }
+    body.push_str(&format!(
+        "</ul>\n<h2>{}</h2>\n<p>{}</p>\n",
+        escape_html(&locale.t("report.streak")),
+        escape_html(&streak_line(report, locale))
+    ));
+
+    if let Some(reflection) = &report.previous_reflection {
+        body.push_str(&format!(
+            "<h2>{}</h2>\n<ul>\n",
+            escape_html(&locale.t_with("report.insightsFrom", &[("period", reflection.period_key.clone())]))
+        ));
+        for insight in insights(reflection) {
+            body.push_str(&format!("<li>{}</li>\n", escape_html(insight)));
+        }
+        body.push_str("</ul>\n");
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>body{{font-family:system-ui,sans-serif;max-width:40em;margin:2em auto}}.done{{color:#888}}</style>\n\
+         </head>\n<body>\n{}</body>\n</html>\n",
+        locale.language(),
+        escape_html(&title),
+        body
+    )
+}
+
+/// Write `<level>-<period_key>.md` and `.html` into `dir`, replacing any
+/// earlier version of the same report. Returns the Markdown path.
+pub fn save_review(report: &ReviewReport, locale: &Locale, dir: &Path) -> anyhow::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+
+    let stem = format!("{}-{}", report.level.as_str(), report.period_key);
+    let markdown_path = dir.join(format!("{stem}.md"));
+    std::fs::write(&markdown_path, render_markdown(report, locale))?;
+    std::fs::write(dir.join(format!("{stem}.html")), render_html(report, locale))?;
+
+    Ok(markdown_path)
+}
+
+/// Directory the review archive is kept in
+pub fn reports_dir<R: Runtime>(app: &AppHandle<R>) -> anyhow::Result<PathBuf> {
+    Ok(crate::db::data_dir(app)?.join("reports"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(goals: Vec<ReviewGoal>, carry_overs: Vec<Goal>) -> ReviewReport {
+        ReviewReport {
+            level: GoalLevel::Weekly,
+            period_key: "2025-W49".into(),
+            period_start: 0,
+            period_end: 1,
+            generated_at: 0,
+            completed: goals.iter().filter(|g| g.goal.is_completed).count() as u32,
+            goals,
+            carry_overs,
+            streak: LevelStreak { level: GoalLevel::Weekly, current: 1, longest: 4 },
+            previous_reflection: None,
+        }
+    }
+
+    #[test]
+    fn test_markdown_lists_goals_children_and_carry_overs() {
+        let mut done = Goal::new("Ship <beta>".into(), GoalLevel::Weekly, 0, None);
+        done.is_completed = true;
+        let open = Goal::new("Write docs".into(), GoalLevel::Weekly, 0, None);
+        let mut child = Goal::new("Fix bug".into(), GoalLevel::Daily, 0, Some(done.id.clone()));
+        child.is_completed = true;
+
+        let report = report(
+            vec![
+                ReviewGoal { goal: done, children: vec![child], children_completed: 1 },
+                ReviewGoal { goal: open.clone(), children: vec![], children_completed: 0 },
+            ],
+            vec![open],
+        );
+
+        let en = Locale::from_setting("en");
+        let md = render_markdown(&report, &en);
+        assert!(md.starts_with("# Weekly review 2025-W49\n"));
+        assert!(md.contains("## Goals (1/2)"));
+        assert!(md.contains("- [x] Ship <beta> (1/1)\n  - [x] Fix bug\n- [ ] Write docs\n"));
+        assert!(md.contains("## Carry-overs\n\n- Write docs\n"));
+        assert!(md.contains("Current: 1 · Longest: 4"));
+
+        let html = render_html(&report, &en);
+        assert!(html.contains("Ship &lt;beta&gt;"));
+        assert!(!html.contains("<beta>"));
+
+        let ja = render_markdown(&report, &Locale::from_setting("ja"));
+        assert!(ja.starts_with("# 週次レビュー 2025-W49\n"));
+        assert!(ja.contains("## 持ち越し\n\n- Write docs\n"));
+    }
+}
@@ -0,0 +1,89 @@
+use std::time::{Duration as StdDuration, Instant};
+use chrono::{DateTime, Duration, Local};
+
+/// Drift between monotonic and wall time tolerated before it counts as a
+/// jump; covers timer slack and NTP slewing
+const TOLERANCE: StdDuration = StdDuration::from_secs(30);
+
+/// Something that moved the wall clock under the scheduler
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockChange {
+    /// The machine slept for longer than the scheduler asked to sleep
+    Resumed,
+    /// The wall clock moved by this much more than the monotonic clock
+    /// (forwards after sleep on platforms where the monotonic clock stops,
+    /// or a manual/NTP clock change; negative when it went backwards)
+    WallClockJumped(Duration),
+    /// The UTC offset changed (time zone or DST switch)
+    TimeZoneChanged,
+}
+
+/// Compares monotonic time with wall time between scheduler ticks
+#[derive(Default)]
+pub struct ClockMonitor {
+    last: Option<(Instant, DateTime<Local>)>,
+}
+
+impl ClockMonitor {
+    /// Record a tick. `expected` is how long the loop meant to sleep since
+    /// the previous tick.
+    pub fn observe(&mut self, mono: Instant, wall: DateTime<Local>, expected: StdDuration) -> Option<ClockChange> {
+        let previous = self.last.replace((mono, wall));
+        let (last_mono, last_wall) = previous?;
+
+        if wall.offset() != last_wall.offset() {
+            return Some(ClockChange::TimeZoneChanged);
+        }
+
+        let mono_elapsed = mono.saturating_duration_since(last_mono);
+        let wall_elapsed = wall - last_wall;
+        let drift = wall_elapsed - Duration::from_std(mono_elapsed).unwrap_or_else(|_| Duration::zero());
+        if drift.abs() > Duration::from_std(TOLERANCE).unwrap() {
+            return Some(ClockChange::WallClockJumped(drift));
+        }
+
+        if mono_elapsed > expected + TOLERANCE {
+            return Some(ClockChange::Resumed);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const MINUTE: StdDuration = StdDuration::from_secs(60);
+
+    #[test]
+    fn test_detects_sleep_and_clock_jumps() {
+        let mut monitor = ClockMonitor::default();
+        let mono = Instant::now();
+        let wall = Local.with_ymd_and_hms(2025, 12, 5, 18, 0, 0).unwrap();
+
+        assert_eq!(monitor.observe(mono, wall, MINUTE), None);
+        // 通常の 1 分間隔
+        assert_eq!(monitor.observe(mono + MINUTE, wall + Duration::minutes(1), MINUTE), None);
+
+        // 週末のスリープ（単調時計は止まっていた）
+        let monday = wall + Duration::days(3);
+        assert_eq!(
+            monitor.observe(mono + 2 * MINUTE, monday, MINUTE),
+            Some(ClockChange::WallClockJumped(Duration::days(3) - Duration::minutes(2))),
+        );
+
+        // スリープ中も単調時計が進む環境
+        assert_eq!(
+            monitor.observe(mono + 62 * MINUTE, monday + Duration::hours(1), MINUTE),
+            Some(ClockChange::Resumed),
+        );
+
+        // 時計が戻された
+        assert_eq!(
+            monitor.observe(mono + 63 * MINUTE, monday, MINUTE),
+            Some(ClockChange::WallClockJumped(-Duration::minutes(61))),
+        );
+    }
+}
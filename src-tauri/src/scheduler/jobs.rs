@@ -0,0 +1,383 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use chrono::{DateTime, Duration, Local, NaiveTime};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use crate::commands::goals::get_week_start_setting;
+use crate::commands::periods::{next_rollover, period_key, period_range};
+use crate::db::Database;
+use crate::models::GoalLevel;
+use crate::notifications::NotificationCenter;
+use crate::planning_reminder::PlanningReminder;
+use crate::reflection_reminder::ReflectionReminder;
+use super::{Cadence, Job, JobRun, Scheduler};
+
+/// Number of nightly backups kept in the backups directory
+const BACKUPS_KEPT: usize = 7;
+
+/// Register the jobs the app runs in the background
+pub fn register_default_jobs(
+    scheduler: &mut Scheduler<AppHandle>,
+    db: &Database,
+    notifications: Arc<NotificationCenter>,
+) {
+    scheduler.register(PeriodWatchJob::default());
+    scheduler.register(ReflectionPromptJob {
+        reminder: ReflectionReminder::new(db.clone(), notifications.clone()),
+    });
+    scheduler.register(PlanningPromptJob {
+        reminder: PlanningReminder::new(db.clone(), notifications.clone()),
+    });
+    scheduler.register(PendingReminderJob { notifications });
+    scheduler.register(TrashPurgeJob);
+    scheduler.register(BackupJob);
+    scheduler.register(UpdateCheckJob);
+}
+
+/// Weekly/monthly/daily reflection prompts
+pub struct ReflectionPromptJob {
+    reminder: ReflectionReminder,
+}
+
+impl Job<AppHandle> for ReflectionPromptJob {
+    fn id(&self) -> &'static str {
+        "reflection_prompts"
+    }
+
+    fn cadence(&self, _db: &Database) -> Cadence {
+        Cadence::Every(Duration::minutes(5))
+    }
+
+    fn run(&self, app: &AppHandle, run: &JobRun) -> Result<(), String> {
+        let last_check = run.last_run.map_or(0, |dt| dt.timestamp_millis());
+        self.reminder.check_and_notify(app, last_check, run.now.timestamp_millis())
+    }
+
+    fn runs_on_clock_change(&self) -> bool {
+        true
+    }
+}
+
+/// One level in a `period-changed` event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodKeyChange {
+    pub level: GoalLevel,
+    pub old_key: String,
+    pub new_key: String,
+}
+
+/// Payload of `period-changed`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodChangedEvent {
+    /// Every level, daily included. `old_key == new_key` for levels whose
+    /// period did not change.
+    pub periods: Vec<PeriodKeyChange>,
+    /// Noticed right after sleep or a wall-clock jump
+    pub clock_changed: bool,
+}
+
+/// A current period in a `period-rolled-over` event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrentPeriod {
+    pub level: GoalLevel,
+    pub key: String,
+    pub start: i64,
+    pub end: i64,
+}
+
+/// Payload of `period-rolled-over`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodRolledOverEvent {
+    /// Levels whose period changed
+    pub rolled_over: Vec<GoalLevel>,
+    /// The current period of every level
+    pub periods: Vec<CurrentPeriod>,
+    pub next_rollover_at: i64,
+    pub clock_changed: bool,
+}
+
+impl PeriodRolledOverEvent {
+    /// Describe the periods at `now` after `change`
+    pub fn new(change: &PeriodChangedEvent, now: DateTime<Local>, week_start: i32) -> Self {
+        let periods = change
+            .periods
+            .iter()
+            .map(|p| {
+                let (start, end) = period_range(p.level, &now, week_start);
+                CurrentPeriod {
+                    level: p.level,
+                    key: p.new_key.clone(),
+                    start: start.timestamp_millis(),
+                    end: end.timestamp_millis(),
+                }
+            })
+            .collect();
+
+        Self {
+            rolled_over: change.periods.iter().filter(|p| p.old_key != p.new_key).map(|p| p.level).collect(),
+            periods,
+            next_rollover_at: next_rollover(&now, week_start).0.timestamp_millis(),
+            clock_changed: change.clock_changed,
+        }
+    }
+}
+
+/// Tells the frontend when the current day, week or month changes. Runs
+/// exactly at the next period start, and after sleep or clock changes
+/// (including the clock moving backwards).
+#[derive(Default)]
+pub struct PeriodWatchJob {
+    last_keys: Mutex<Option<Vec<String>>>,
+}
+
+impl PeriodWatchJob {
+    /// Compare the period keys at `now` with the ones seen last time
+    pub fn observe(&self, now: DateTime<Local>, week_start: i32, clock_changed: bool) -> Option<PeriodChangedEvent> {
+        let levels = [GoalLevel::Daily, GoalLevel::Weekly, GoalLevel::Monthly];
+        let keys: Vec<String> = levels
+            .iter()
+            .map(|&level| period_key(level, now.timestamp_millis(), week_start))
+            .collect::<Option<_>>()?;
+
+        let previous = self.last_keys.lock().unwrap().replace(keys.clone())?;
+        if previous == keys {
+            return None;
+        }
+
+        Some(PeriodChangedEvent {
+            periods: levels
+                .into_iter()
+                .zip(previous.into_iter().zip(keys))
+                .map(|(level, (old_key, new_key))| PeriodKeyChange { level, old_key, new_key })
+                .collect(),
+            clock_changed,
+        })
+    }
+}
+
+impl Job<AppHandle> for PeriodWatchJob {
+    fn id(&self) -> &'static str {
+        "period_watch"
+    }
+
+    fn cadence(&self, db: &Database) -> Cadence {
+        Cadence::PeriodRollover { week_start: get_week_start_setting(db) }
+    }
+
+    fn run(&self, app: &AppHandle, run: &JobRun) -> Result<(), String> {
+        let db: tauri::State<Database> = app.state();
+        let week_start = get_week_start_setting(db.inner());
+        let Some(event) = self.observe(run.now, week_start, run.clock_changed) else {
+            return Ok(());
+        };
+
+        let rolled_over = PeriodRolledOverEvent::new(&event, run.now, week_start);
+        println!("[Scheduler] Emitting period-rolled-over event: {:?}", rolled_over);
+        app.emit("period-changed", event)
+            .and_then(|_| app.emit("period-rolled-over", rolled_over))
+            .map_err(|e| format!("Failed to emit event: {}", e))
+    }
+
+    fn runs_on_clock_change(&self) -> bool {
+        true
+    }
+}
+
+/// Nudges to set goals, and to finish them before a period ends
+pub struct PlanningPromptJob {
+    reminder: PlanningReminder,
+}
+
+impl Job<AppHandle> for PlanningPromptJob {
+    fn id(&self) -> &'static str {
+        "planning_prompts"
+    }
+
+    fn cadence(&self, _db: &Database) -> Cadence {
+        Cadence::Every(Duration::minutes(5))
+    }
+
+    fn run(&self, _app: &AppHandle, run: &JobRun) -> Result<(), String> {
+        self.reminder.check_and_notify(run.now.timestamp_millis())?;
+        Ok(())
+    }
+
+    fn runs_on_clock_change(&self) -> bool {
+        true
+    }
+}
+
+/// Deliver reminders held back by a snooze, pause or quiet hours once the
+/// hold is over
+pub struct PendingReminderJob {
+    notifications: Arc<NotificationCenter>,
+}
+
+impl Job<AppHandle> for PendingReminderJob {
+    fn id(&self) -> &'static str {
+        "pending_reminders"
+    }
+
+    fn cadence(&self, _db: &Database) -> Cadence {
+        Cadence::Every(Duration::minutes(1))
+    }
+
+    fn run(&self, _app: &AppHandle, run: &JobRun) -> Result<(), String> {
+        self.notifications.deliver_pending(run.now.timestamp_millis())?;
+        Ok(())
+    }
+}
+
+/// Drop goals whose trash retention period has passed
+pub struct TrashPurgeJob;
+
+impl Job<AppHandle> for TrashPurgeJob {
+    fn id(&self) -> &'static str {
+        "trash_purge"
+    }
+
+    fn cadence(&self, _db: &Database) -> Cadence {
+        Cadence::Every(Duration::hours(6))
+    }
+
+    fn run(&self, app: &AppHandle, _run: &JobRun) -> Result<(), String> {
+        let db: tauri::State<Database> = app.state();
+        let purged = crate::commands::goals::purge_expired_trash(db.inner())?;
+        if purged > 0 {
+            crate::commands::goals::broadcast_goals_updated(app);
+        }
+        Ok(())
+    }
+}
+
+/// Nightly copy of the database into `backups/`
+pub struct BackupJob;
+
+impl Job<AppHandle> for BackupJob {
+    fn id(&self) -> &'static str {
+        "backup"
+    }
+
+    fn cadence(&self, _db: &Database) -> Cadence {
+        Cadence::DailyAt(NaiveTime::from_hms_opt(3, 0, 0).unwrap())
+    }
+
+    fn run(&self, app: &AppHandle, run: &JobRun) -> Result<(), String> {
+        let dir = crate::db::data_dir(app)
+            .map_err(|e| e.to_string())?
+            .join("backups");
+        let db: tauri::State<Database> = app.state();
+        write_backup(db.inner(), &dir, &run.now.format("%Y-%m-%d").to_string())
+    }
+}
+
+/// Back up into `dir/trivyn-{date}.db` and keep only the newest backups
+pub fn write_backup(db: &Database, dir: &Path, date: &str) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+
+    let path = dir.join(format!("trivyn-{}.db", date));
+    if !path.exists() {
+        db.backup_to(&path).map_err(|e| e.to_string())?;
+    }
+
+    // ファイル名に日付が入っているので名前順 = 古い順
+    let mut backups: Vec<_> = std::fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("trivyn-") && n.ends_with(".db"))
+        })
+        .collect();
+    backups.sort();
+
+    let excess = backups.len().saturating_sub(BACKUPS_KEPT);
+    for old in &backups[..excess] {
+        std::fs::remove_file(old).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Ask the frontend to check for an app update; the updater plugin lives there
+pub struct UpdateCheckJob;
+
+impl Job<AppHandle> for UpdateCheckJob {
+    fn id(&self) -> &'static str {
+        "update_check"
+    }
+
+    fn cadence(&self, _db: &Database) -> Cadence {
+        Cadence::Every(Duration::hours(6))
+    }
+
+    fn run(&self, app: &AppHandle, run: &JobRun) -> Result<(), String> {
+        // 起動時のチェックはフロントエンドが行うので初回は記録だけ
+        if run.last_run.is_none() {
+            return Ok(());
+        }
+        app.emit("update-check-due", ())
+            .map_err(|e| format!("Failed to emit event: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_period_watch_reports_every_level() {
+        let watch = PeriodWatchJob::default();
+        let sunday = Local.with_ymd_and_hms(2025, 11, 30, 23, 59, 0).unwrap();
+
+        // 初回は基準を記録するだけ
+        assert_eq!(watch.observe(sunday, 2, false), None);
+        assert_eq!(watch.observe(sunday, 2, false), None);
+
+        let monday = sunday + Duration::minutes(1);
+        let event = watch.observe(monday, 2, false).unwrap();
+        let keys: Vec<_> = event.periods.iter().map(|p| (p.old_key.as_str(), p.new_key.as_str())).collect();
+        assert_eq!(keys, [("2025-11-30", "2025-12-01"), ("2025-W48", "2025-W49"), ("2025-11", "2025-12")]);
+
+        let rolled = PeriodRolledOverEvent::new(&event, monday, 2);
+        assert_eq!(rolled.rolled_over, [GoalLevel::Daily, GoalLevel::Weekly, GoalLevel::Monthly]);
+        assert_eq!(rolled.periods[1].start, monday.timestamp_millis());
+        assert_eq!(rolled.periods[1].end, (monday + Duration::days(7)).timestamp_millis());
+        assert_eq!(rolled.next_rollover_at, (monday + Duration::days(1)).timestamp_millis());
+
+        // 時計が戻ったときも古い期間へのキー変更として通知する
+        let event = watch.observe(sunday, 2, true).unwrap();
+        assert!(event.clock_changed);
+        assert_eq!(event.periods[0].new_key, "2025-11-30");
+    }
+
+    #[test]
+    fn test_write_backup_keeps_newest() {
+        let db = Database::new(":memory:".into()).unwrap();
+        db.migrate().unwrap();
+        let dir = std::env::temp_dir().join(format!("trivyn-backups-{}", uuid::Uuid::new_v4()));
+
+        for day in 1..=9 {
+            write_backup(&db, &dir, &format!("2025-12-{:02}", day)).unwrap();
+        }
+        // 同じ日に二度走っても上書きしない
+        write_backup(&db, &dir, "2025-12-09").unwrap();
+
+        let mut names: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(names.len(), BACKUPS_KEPT);
+        assert_eq!(names.first().map(String::as_str), Some("trivyn-2025-12-03.db"));
+        assert_eq!(names.last().map(String::as_str), Some("trivyn-2025-12-09.db"));
+    }
+}
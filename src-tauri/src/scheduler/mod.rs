@@ -0,0 +1,381 @@
+pub mod jobs;
+pub mod clock_monitor;
+
+use std::sync::Arc;
+use std::time::{Duration as StdDuration, Instant};
+use chrono::{DateTime, Duration, Local, NaiveTime};
+use crate::commands::periods::{local_midnight, next_rollover};
+use crate::db::Database;
+use clock_monitor::ClockMonitor;
+
+/// Longest the background loop sleeps between checks, so settings changes
+/// and newly due jobs are picked up reasonably quickly
+const MAX_SLEEP: StdDuration = StdDuration::from_secs(60);
+
+/// Shortest sleep, so a job due right away cannot spin the loop while
+/// jobs due in a moment still run on time
+const MIN_SLEEP: StdDuration = StdDuration::from_millis(100);
+
+/// Source of the current time, replaceable in tests
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Local>;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// Clock that only moves when told to
+#[cfg(test)]
+pub struct FakeClock {
+    now: std::sync::Mutex<DateTime<Local>>,
+}
+
+#[cfg(test)]
+impl FakeClock {
+    pub fn new(now: DateTime<Local>) -> Self {
+        Self { now: std::sync::Mutex::new(now) }
+    }
+
+    pub fn set(&self, now: DateTime<Local>) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+}
+
+#[cfg(test)]
+impl Clock for FakeClock {
+    fn now(&self) -> DateTime<Local> {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// How often a job runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cadence {
+    /// A fixed interval after the previous run
+    Every(Duration),
+    /// Once a day at a local time, caught up on the next check if missed
+    DailyAt(NaiveTime),
+    /// At the start of the next day, week or month after the previous run
+    PeriodRollover { week_start: i32 },
+    Disabled,
+}
+
+impl Cadence {
+    /// When the job is due, given its last run. A job that never ran is
+    /// due right away, except daily jobs which wait for today's time.
+    pub fn next_run(&self, last_run: Option<DateTime<Local>>, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        match *self {
+            Cadence::Every(interval) => Some(last_run.map_or(now, |last| last + interval)),
+            Cadence::DailyAt(time) => {
+                let at = |date: chrono::NaiveDate| {
+                    local_midnight(date) + (time - NaiveTime::MIN)
+                };
+                match last_run {
+                    None => Some(at(now.date_naive())),
+                    Some(last) => {
+                        let same_day = at(last.date_naive());
+                        Some(if same_day > last { same_day } else { at(last.date_naive().succ_opt()?) })
+                    }
+                }
+            }
+            Cadence::PeriodRollover { week_start } => {
+                Some(last_run.map_or(now, |last| next_rollover(&last, week_start).0))
+            }
+            Cadence::Disabled => None,
+        }
+    }
+}
+
+/// What a job gets to know about the run it is asked to do
+#[derive(Debug, Clone, Copy)]
+pub struct JobRun {
+    pub now: DateTime<Local>,
+    pub last_run: Option<DateTime<Local>>,
+    /// Run right after the machine woke up or the wall clock jumped
+    pub clock_changed: bool,
+}
+
+/// A unit of background work. `C` is whatever the job needs to act on:
+/// the app handle in the app, a recorder in tests.
+pub trait Job<C>: Send + Sync {
+    /// Stable id the run state is persisted under
+    fn id(&self) -> &'static str;
+
+    /// Read on every check, so cadences can follow settings
+    fn cadence(&self, db: &Database) -> Cadence;
+
+    fn run(&self, ctx: &C, run: &JobRun) -> Result<(), String>;
+
+    /// Whether to run right away after sleep or a clock change, whatever
+    /// the cadence says
+    fn runs_on_clock_change(&self) -> bool {
+        false
+    }
+}
+
+/// Runs registered jobs when they are due and persists when they last ran
+pub struct Scheduler<C> {
+    db: Database,
+    clock: Arc<dyn Clock>,
+    jobs: Vec<Box<dyn Job<C>>>,
+}
+
+impl<C> Scheduler<C> {
+    pub fn new(db: Database, clock: Arc<dyn Clock>) -> Self {
+        Self { db, clock, jobs: Vec::new() }
+    }
+
+    pub fn register(&mut self, job: impl Job<C> + 'static) {
+        self.jobs.push(Box::new(job));
+    }
+
+    pub fn now(&self) -> DateTime<Local> {
+        self.clock.now()
+    }
+
+    fn last_run(&self, job_id: &str) -> Option<DateTime<Local>> {
+        self.db
+            .get_job_state(job_id)
+            .ok()
+            .flatten()
+            .and_then(|state| state.last_run_at)
+            .and_then(DateTime::from_timestamp_millis)
+            .map(|dt| dt.with_timezone(&Local))
+    }
+
+    /// Run every job that is due now. Returns the ids of the jobs that ran.
+    pub fn run_due(&self, ctx: &C) -> Vec<&'static str> {
+        let now = self.clock.now();
+        let mut ran = Vec::new();
+
+        for job in &self.jobs {
+            let cadence = job.cadence(&self.db);
+            let last_run = self.last_run(job.id());
+            let Some(due) = cadence.next_run(last_run, now) else { continue };
+            // 前回実行が未来なら時計が戻っている。次回予定は当てにならないので今すぐ実行
+            let clock_went_back = last_run.is_some_and(|last| last > now);
+            if due > now && !clock_went_back {
+                continue;
+            }
+
+            self.run_job(job.as_ref(), ctx, now, last_run, false);
+            ran.push(job.id());
+        }
+
+        ran
+    }
+
+    /// Run the jobs that care about sleep and clock changes right away
+    pub fn run_after_clock_change(&self, ctx: &C) -> Vec<&'static str> {
+        let now = self.clock.now();
+        let mut ran = Vec::new();
+
+        for job in self.jobs.iter().filter(|job| job.runs_on_clock_change()) {
+            if job.cadence(&self.db) == Cadence::Disabled {
+                continue;
+            }
+            let last_run = self.last_run(job.id());
+            self.run_job(job.as_ref(), ctx, now, last_run, true);
+            ran.push(job.id());
+        }
+
+        ran
+    }
+
+    fn run_job(
+        &self,
+        job: &dyn Job<C>,
+        ctx: &C,
+        now: DateTime<Local>,
+        last_run: Option<DateTime<Local>>,
+        clock_changed: bool,
+    ) {
+        let result = job.run(ctx, &JobRun { now, last_run, clock_changed });
+        if let Err(ref e) = result {
+            eprintln!("[Scheduler] Job {} failed: {}", job.id(), e);
+        }
+
+        let next = job.cadence(&self.db).next_run(Some(now), now).map(|dt| dt.timestamp_millis());
+        if let Err(e) = self.db.record_job_run(job.id(), now.timestamp_millis(), next, result.err().as_deref()) {
+            eprintln!("[Scheduler] Failed to record run of {}: {}", job.id(), e);
+        }
+    }
+
+    /// Earliest time any job is due
+    pub fn next_due(&self) -> Option<DateTime<Local>> {
+        let now = self.clock.now();
+        self.jobs
+            .iter()
+            .filter_map(|job| job.cadence(&self.db).next_run(self.last_run(job.id()), now))
+            .min()
+    }
+
+    /// How long the background loop should sleep before checking again
+    pub fn sleep_duration(&self) -> StdDuration {
+        self.next_due()
+            .and_then(|due| (due - self.clock.now()).to_std().ok())
+            .unwrap_or(StdDuration::ZERO)
+            .clamp(MIN_SLEEP, MAX_SLEEP)
+    }
+}
+
+/// Run due jobs until the app exits. Every tick also checks whether the
+/// machine slept or the wall clock jumped since the previous one.
+pub async fn run_loop<C: Send + Sync + 'static>(scheduler: Arc<Scheduler<C>>, ctx: C) {
+    let mut monitor = ClockMonitor::default();
+    let mut slept = StdDuration::ZERO;
+
+    loop {
+        if let Some(change) = monitor.observe(Instant::now(), scheduler.now(), slept) {
+            println!("[Scheduler] Clock change detected: {:?}", change);
+            scheduler.run_after_clock_change(&ctx);
+        }
+        scheduler.run_due(&ctx);
+
+        slept = scheduler.sleep_duration();
+        tokio::time::sleep(slept).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use chrono::TimeZone;
+
+    struct CountingJob {
+        id: &'static str,
+        cadence: Cadence,
+    }
+
+    impl Job<Mutex<Vec<(&'static str, Option<DateTime<Local>>)>>> for CountingJob {
+        fn id(&self) -> &'static str {
+            self.id
+        }
+
+        fn cadence(&self, _db: &Database) -> Cadence {
+            self.cadence
+        }
+
+        fn run(&self, ctx: &Mutex<Vec<(&'static str, Option<DateTime<Local>>)>>, run: &JobRun) -> Result<(), String> {
+            ctx.lock().unwrap().push((self.id, run.last_run));
+            Ok(())
+        }
+    }
+
+    fn at(d: u32, h: u32, m: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 12, d, h, m, 0).unwrap()
+    }
+
+    fn test_db() -> Database {
+        let db = Database::new(":memory:".into()).unwrap();
+        db.migrate().unwrap();
+        db
+    }
+
+    #[test]
+    fn test_daily_cadence_catches_up_after_missed_time() {
+        let cadence = Cadence::DailyAt(NaiveTime::from_hms_opt(3, 0, 0).unwrap());
+
+        assert_eq!(cadence.next_run(None, at(1, 9, 0)), Some(at(1, 3, 0)));
+        assert_eq!(cadence.next_run(Some(at(1, 2, 0)), at(1, 9, 0)), Some(at(1, 3, 0)));
+        assert_eq!(cadence.next_run(Some(at(1, 3, 0)), at(1, 9, 0)), Some(at(2, 3, 0)));
+        assert_eq!(Cadence::Disabled.next_run(None, at(1, 9, 0)), None);
+    }
+
+    #[test]
+    fn test_period_rollover_cadence_runs_at_midnight() {
+        let clock = Arc::new(FakeClock::new(at(1, 23, 58)));
+        let mut scheduler = Scheduler::new(test_db(), clock.clone());
+        scheduler.register(CountingJob { id: "rollover", cadence: Cadence::PeriodRollover { week_start: 2 } });
+        let runs = Mutex::new(Vec::new());
+
+        assert_eq!(scheduler.run_due(&runs), ["rollover"]);
+        assert_eq!(scheduler.next_due(), Some(at(2, 0, 0)));
+        assert_eq!(scheduler.sleep_duration(), MAX_SLEEP);
+
+        clock.set(at(1, 23, 59) + Duration::milliseconds(59_500));
+        assert_eq!(scheduler.sleep_duration(), StdDuration::from_millis(500));
+        assert!(scheduler.run_due(&runs).is_empty());
+
+        clock.set(at(2, 0, 0));
+        assert_eq!(scheduler.run_due(&runs), ["rollover"]);
+        assert_eq!(scheduler.next_due(), Some(at(3, 0, 0)));
+    }
+
+    #[test]
+    fn test_jobs_run_on_their_cadence_with_fake_clock() {
+        let clock = Arc::new(FakeClock::new(at(1, 9, 0)));
+        let mut scheduler = Scheduler::new(test_db(), clock.clone());
+        scheduler.register(CountingJob { id: "often", cadence: Cadence::Every(Duration::minutes(5)) });
+        scheduler.register(CountingJob {
+            id: "nightly",
+            cadence: Cadence::DailyAt(NaiveTime::from_hms_opt(3, 0, 0).unwrap()),
+        });
+        let runs = Mutex::new(Vec::new());
+
+        // Both run on the first check: "nightly" missed 03:00 today
+        assert_eq!(scheduler.run_due(&runs), ["often", "nightly"]);
+        assert!(scheduler.run_due(&runs).is_empty());
+
+        clock.advance(Duration::minutes(4));
+        assert!(scheduler.run_due(&runs).is_empty());
+        assert_eq!(scheduler.next_due(), Some(at(1, 9, 5)));
+
+        clock.advance(Duration::minutes(1));
+        assert_eq!(scheduler.run_due(&runs), ["often"]);
+
+        clock.set(at(2, 3, 0));
+        assert_eq!(scheduler.run_due(&runs), ["often", "nightly"]);
+
+        let runs = runs.into_inner().unwrap();
+        assert_eq!(runs[2], ("often", Some(at(1, 9, 0))));
+        assert_eq!(runs.last(), Some(&("nightly", Some(at(1, 9, 0)))));
+    }
+
+    #[test]
+    fn test_clock_moving_backwards_and_clock_changes() {
+        let clock = Arc::new(FakeClock::new(at(2, 9, 0)));
+        let mut scheduler = Scheduler::new(test_db(), clock.clone());
+        scheduler.register(CountingJob { id: "often", cadence: Cadence::Every(Duration::minutes(5)) });
+        let runs = Mutex::new(Vec::new());
+        scheduler.run_due(&runs);
+
+        // 前回実行より前に戻った時計でも待たされない
+        clock.set(at(1, 9, 0));
+        assert_eq!(scheduler.run_due(&runs), ["often"]);
+
+        // CountingJob は時計の変化に反応しない
+        assert!(scheduler.run_after_clock_change(&runs).is_empty());
+    }
+
+    #[test]
+    fn test_last_run_is_persisted() {
+        let db = test_db();
+        let clock = Arc::new(FakeClock::new(at(1, 9, 0)));
+        let runs = Mutex::new(Vec::new());
+
+        let mut first = Scheduler::new(db.clone(), clock.clone());
+        first.register(CountingJob { id: "often", cadence: Cadence::Every(Duration::minutes(5)) });
+        first.run_due(&runs);
+
+        // A new scheduler (e.g. after a restart) continues from the stored run
+        clock.advance(Duration::minutes(2));
+        let mut second = Scheduler::new(db.clone(), clock.clone());
+        second.register(CountingJob { id: "often", cadence: Cadence::Every(Duration::minutes(5)) });
+        assert!(second.run_due(&runs).is_empty());
+
+        let state = db.get_job_state("often").unwrap().unwrap();
+        assert_eq!(state.run_count, 1);
+        assert_eq!(state.next_run_at, Some(at(1, 9, 5).timestamp_millis()));
+    }
+}
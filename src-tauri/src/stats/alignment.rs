@@ -0,0 +1,145 @@
+use chrono::{DateTime, Local};
+use crate::commands::periods::{period_key, period_range};
+use crate::db::Database;
+use crate::models::{AlignmentReport, Goal, GoalLevel, ParentAlignment};
+
+/// Alignment report for the week or month of `level` containing `date`
+pub fn compute_alignment(
+    db: &Database,
+    level: GoalLevel,
+    date: &DateTime<Local>,
+    week_start: i32,
+) -> anyhow::Result<AlignmentReport> {
+    if level == GoalLevel::Daily {
+        anyhow::bail!("Alignment is only available for weekly and monthly goals");
+    }
+
+    let (start, end) = period_range(level, date, week_start);
+    let (from, to) = (start.timestamp_millis(), end.timestamp_millis());
+
+    let parents = db.get_goals_in_range(level.as_str(), from, to)?;
+    let weekly = match level {
+        GoalLevel::Monthly => db.get_goals_in_range(GoalLevel::Weekly.as_str(), from, to)?,
+        _ => Vec::new(),
+    };
+    let daily = db.get_goals_in_range(GoalLevel::Daily.as_str(), from, to)?;
+
+    Ok(build_alignment(
+        level,
+        period_key(level, from, week_start).unwrap_or_default(),
+        from,
+        to,
+        parents,
+        &weekly,
+        &daily,
+    ))
+}
+
+fn children_of<'a>(goals: &'a [Goal], parent_id: &str) -> Vec<&'a Goal> {
+    goals
+        .iter()
+        .filter(|g| g.parent_goal_id.as_deref() == Some(parent_id))
+        .collect()
+}
+
+/// Assemble the report from the goals of one period. For weekly reports
+/// `weekly` is empty and the parents' children are the daily goals.
+pub fn build_alignment(
+    level: GoalLevel,
+    period_key: String,
+    period_start: i64,
+    period_end: i64,
+    parents: Vec<Goal>,
+    weekly: &[Goal],
+    daily: &[Goal],
+) -> AlignmentReport {
+    let daily_total = daily.len() as u32;
+    let daily_linked = daily.iter().filter(|g| g.parent_goal_id.is_some()).count() as u32;
+
+    let parents: Vec<ParentAlignment> = parents
+        .into_iter()
+        .map(|goal| {
+            let (children, daily_effort) = match level {
+                GoalLevel::Monthly => {
+                    let children = children_of(weekly, &goal.id);
+                    let effort: Vec<&Goal> = children
+                        .iter()
+                        .flat_map(|weekly_goal| children_of(daily, &weekly_goal.id))
+                        .collect();
+                    (children, effort)
+                }
+                _ => {
+                    let children = children_of(daily, &goal.id);
+                    (children.clone(), children)
+                }
+            };
+
+            ParentAlignment {
+                linked_children: children.len() as u32,
+                completed_children: children.iter().filter(|g| g.is_completed).count() as u32,
+                daily_effort: daily_effort.len() as u32,
+                daily_completed: daily_effort.iter().filter(|g| g.is_completed).count() as u32,
+                goal,
+            }
+        })
+        .collect();
+
+    let neglected = parents
+        .iter()
+        .filter(|p| p.daily_effort == 0)
+        .map(|p| p.goal.clone())
+        .collect();
+
+    AlignmentReport {
+        level,
+        period_key,
+        period_start,
+        period_end,
+        daily_total,
+        daily_linked,
+        daily_linked_share: if daily_total == 0 {
+            0.0
+        } else {
+            daily_linked as f64 / daily_total as f64
+        },
+        linked_children: parents.iter().map(|p| p.linked_children).sum(),
+        linked_children_completed: parents.iter().map(|p| p.completed_children).sum(),
+        parents,
+        neglected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monthly_alignment_counts_daily_effort_through_weekly_goals() {
+        let backed = Goal::new("Backed".into(), GoalLevel::Monthly, 0, None);
+        let idle = Goal::new("Idle".into(), GoalLevel::Monthly, 0, None);
+        let mut weekly = Goal::new("Weekly".into(), GoalLevel::Weekly, 0, Some(backed.id.clone()));
+        weekly.is_completed = true;
+        let mut linked = Goal::new("Linked".into(), GoalLevel::Daily, 0, Some(weekly.id.clone()));
+        linked.is_completed = true;
+        let loose = Goal::new("Loose".into(), GoalLevel::Daily, 0, None);
+
+        let report = build_alignment(
+            GoalLevel::Monthly,
+            "2025-12".into(),
+            0,
+            1,
+            vec![backed.clone(), idle.clone()],
+            &[weekly],
+            &[linked, loose],
+        );
+
+        assert_eq!(report.daily_total, 2);
+        assert_eq!(report.daily_linked, 1);
+        assert_eq!(report.daily_linked_share, 0.5);
+        assert_eq!(report.linked_children, 1);
+        assert_eq!(report.linked_children_completed, 1);
+        assert_eq!(report.parents[0].daily_effort, 1);
+        assert_eq!(report.parents[0].daily_completed, 1);
+        assert_eq!(report.neglected, vec![idle]);
+    }
+}
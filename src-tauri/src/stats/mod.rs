@@ -0,0 +1,163 @@
+pub mod alignment;
+
+use chrono::{DateTime, Local, NaiveDate};
+use crate::commands::goals::MAX_GOALS_PER_PERIOD;
+use crate::commands::periods::{next_period_start, local_midnight, period_range};
+use crate::db::stats::PeriodSummary;
+use crate::db::Database;
+use crate::models::{GoalLevel, GoalStats, LevelCompletion, LevelCompletionTime, LevelStreak};
+
+const LEVELS: [GoalLevel; 3] = [GoalLevel::Daily, GoalLevel::Weekly, GoalLevel::Monthly];
+
+/// Compute completion rates, streaks, time-to-complete and completion
+/// patterns for goals whose period starts in `[from, to)`.
+pub fn compute_stats(
+    db: &Database,
+    from: i64,
+    to: i64,
+    now: &DateTime<Local>,
+    week_start: i32,
+) -> anyhow::Result<GoalStats> {
+    let counts = db.completion_counts(from, to)?;
+    let completion = LEVELS
+        .iter()
+        .map(|&level| {
+            let (total, completed) = counts
+                .iter()
+                .find(|(l, _, _)| *l == level)
+                .map(|&(_, total, completed)| (total, completed))
+                .unwrap_or((0, 0));
+            LevelCompletion {
+                level,
+                total,
+                completed,
+                rate: if total == 0 { 0.0 } else { completed as f64 / total as f64 },
+            }
+        })
+        .collect();
+
+    let averages = db.average_completion_times(from, to)?;
+    let time_to_complete = LEVELS
+        .iter()
+        .map(|&level| {
+            let found = averages.iter().find(|(l, _, _)| *l == level);
+            LevelCompletionTime {
+                level,
+                average_ms: found.map(|&(_, average, _)| average),
+                sample_size: found.map(|&(_, _, count)| count).unwrap_or(0),
+            }
+        })
+        .collect();
+
+    let mut streaks = Vec::new();
+    for level in LEVELS {
+        let summaries = db.period_summaries(level, week_start)?;
+        let (current_start, _) = period_range(level, now, week_start);
+        let (current, longest) = streaks_for(level, &summaries, current_start.date_naive());
+        streaks.push(LevelStreak { level, current, longest });
+    }
+
+    Ok(GoalStats {
+        from,
+        to,
+        completion,
+        streaks,
+        time_to_complete,
+        completions_by_weekday: db.completions_by_weekday(from, to)?,
+        completions_by_hour: db.completions_by_hour(from, to)?,
+        uncompleted_count: db.count_goal_events("uncompleted", from, to)?,
+    })
+}
+
+/// A period counts towards a streak when all three of its goals are done
+fn is_all_done(summary: &PeriodSummary) -> bool {
+    summary.total as usize >= MAX_GOALS_PER_PERIOD && summary.completed == summary.total
+}
+
+fn next_period_date(level: GoalLevel, date: NaiveDate) -> NaiveDate {
+    next_period_start(level, &local_midnight(date)).date_naive()
+}
+
+/// Current and longest streak of "all three done" periods.
+/// `summaries` must be sorted by period start. An unfinished current period
+/// does not break the streak, it just does not count yet.
+pub fn streaks_for(level: GoalLevel, summaries: &[PeriodSummary], current_period: NaiveDate) -> (u32, u32) {
+    let done: Vec<NaiveDate> = summaries
+        .iter()
+        .filter(|s| is_all_done(s))
+        .map(|s| s.period_start)
+        .collect();
+
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for &date in &done {
+        run = match previous {
+            Some(prev) if next_period_date(level, prev) == date => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(date);
+    }
+
+    // `run` is the streak ending at the last completed period; it is still
+    // current if that period is this one or the one right before it
+    let current = match previous {
+        Some(last) if last == current_period || next_period_date(level, last) == current_period => run,
+        _ => 0,
+    };
+
+    (current, longest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(y: i32, m: u32, d: u32, total: u32, completed: u32) -> PeriodSummary {
+        PeriodSummary {
+            period_start: NaiveDate::from_ymd_opt(y, m, d).unwrap(),
+            total,
+            completed,
+        }
+    }
+
+    #[test]
+    fn test_daily_streaks() {
+        let summaries = vec![
+            summary(2025, 12, 1, 3, 3),
+            summary(2025, 12, 2, 3, 3),
+            summary(2025, 12, 3, 3, 3),
+            summary(2025, 12, 4, 3, 2), // breaks the streak
+            summary(2025, 12, 5, 3, 3),
+            summary(2025, 12, 6, 3, 3),
+            summary(2025, 12, 7, 2, 1), // today, still in progress
+        ];
+        let today = NaiveDate::from_ymd_opt(2025, 12, 7).unwrap();
+
+        assert_eq!(streaks_for(GoalLevel::Daily, &summaries, today), (2, 3));
+    }
+
+    #[test]
+    fn test_streak_ends_when_a_period_is_skipped() {
+        let summaries = vec![
+            summary(2025, 12, 1, 3, 3),
+            summary(2025, 12, 2, 3, 3),
+        ];
+        let today = NaiveDate::from_ymd_opt(2025, 12, 4).unwrap();
+
+        assert_eq!(streaks_for(GoalLevel::Daily, &summaries, today), (0, 2));
+    }
+
+    #[test]
+    fn test_monthly_streak_across_year_boundary() {
+        let summaries = vec![
+            summary(2025, 11, 1, 3, 3),
+            summary(2025, 12, 1, 3, 3),
+            summary(2026, 1, 1, 3, 3),
+        ];
+        let this_month = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+
+        assert_eq!(streaks_for(GoalLevel::Monthly, &summaries, this_month), (3, 3));
+    }
+}
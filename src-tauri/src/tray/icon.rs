@@ -0,0 +1,126 @@
+// Tray icon drawn at runtime: the triangle split into three segments, one
+// filled per completed goal
+
+/// Width and height of the generated icon in pixels
+pub const ICON_SIZE: u32 = 32;
+
+/// Samples per pixel side used for anti-aliasing
+const SUPERSAMPLE: u32 = 4;
+
+/// Gap between the triangle and the icon edges
+const MARGIN: f32 = 2.0;
+
+/// Width of the outline and of the lines between segments
+const OUTLINE: f32 = 2.0;
+const DIVIDER: f32 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IconStyle {
+    /// Black on transparent; macOS tints template images to fit the menu bar
+    Template,
+    /// Dark glyph for light taskbars
+    Light,
+    /// Light glyph for dark taskbars
+    Dark,
+}
+
+impl IconStyle {
+    fn color(self) -> [u8; 3] {
+        match self {
+            IconStyle::Template => [0, 0, 0],
+            IconStyle::Light => [0x1F, 0x29, 0x37],
+            IconStyle::Dark => [0xF9, 0xFA, 0xFB],
+        }
+    }
+}
+
+/// RGBA pixels (`ICON_SIZE` x `ICON_SIZE`) of the triangle with `filled`
+/// of its three segments filled: bottom first, then right, then left
+pub fn render_progress_icon(filled: usize, style: IconStyle) -> Vec<u8> {
+    let size = ICON_SIZE as f32;
+    // 上向きの正三角形
+    let side = size - 2.0 * MARGIN;
+    let height = side * 3f32.sqrt() / 2.0;
+    let top = (size - height) / 2.0;
+    let vertices = [
+        (size / 2.0, top),
+        (MARGIN, top + height),
+        (size - MARGIN, top + height),
+    ];
+
+    let [r, g, b] = style.color();
+    let mut pixels = Vec::with_capacity((ICON_SIZE * ICON_SIZE * 4) as usize);
+    for y in 0..ICON_SIZE {
+        for x in 0..ICON_SIZE {
+            let mut covered = 0;
+            for sy in 0..SUPERSAMPLE {
+                for sx in 0..SUPERSAMPLE {
+                    let px = x as f32 + (sx as f32 + 0.5) / SUPERSAMPLE as f32;
+                    let py = y as f32 + (sy as f32 + 0.5) / SUPERSAMPLE as f32;
+                    if is_ink(barycentric(&vertices, (px, py)), height, filled) {
+                        covered += 1;
+                    }
+                }
+            }
+            let alpha = (covered * 255 / (SUPERSAMPLE * SUPERSAMPLE)) as u8;
+            pixels.extend_from_slice(&[r, g, b, alpha]);
+        }
+    }
+    pixels
+}
+
+/// Barycentric coordinates of `p`. In an equilateral triangle coordinate
+/// `i` times the height is the distance to the edge opposite vertex `i`.
+fn barycentric(v: &[(f32, f32); 3], p: (f32, f32)) -> [f32; 3] {
+    let det = (v[1].1 - v[2].1) * (v[0].0 - v[2].0) + (v[2].0 - v[1].0) * (v[0].1 - v[2].1);
+    let l0 = ((v[1].1 - v[2].1) * (p.0 - v[2].0) + (v[2].0 - v[1].0) * (p.1 - v[2].1)) / det;
+    let l1 = ((v[2].1 - v[0].1) * (p.0 - v[2].0) + (v[0].0 - v[2].0) * (p.1 - v[2].1)) / det;
+    [l0, l1, 1.0 - l0 - l1]
+}
+
+fn is_ink(l: [f32; 3], height: f32, filled: usize) -> bool {
+    if l.iter().any(|&c| c < 0.0) {
+        return false;
+    }
+
+    // 点は最も近い辺の側のセグメントに属する（0 = 底辺, 1 = 右辺, 2 = 左辺）
+    let mut order = [0, 1, 2];
+    order.sort_by(|&a, &b| l[a].total_cmp(&l[b]));
+    let (nearest, second) = (order[0], order[1]);
+
+    let on_outline = l[nearest] * height < OUTLINE;
+    // 隣り合うセグメントの境界（重心から頂点への線）までの距離
+    let on_divider = (l[second] - l[nearest]) * height / 3f32.sqrt() < DIVIDER / 2.0;
+
+    on_outline || on_divider || nearest < filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(pixels: &[u8], x: u32, y: u32) -> &[u8] {
+        let i = ((y * ICON_SIZE + x) * 4) as usize;
+        &pixels[i..i + 4]
+    }
+
+    #[test]
+    fn test_segments_fill_in_order() {
+        // 底辺側と右辺側のセグメントの内側
+        let (bottom, right) = ((16, 25), (20, 17));
+
+        let empty = render_progress_icon(0, IconStyle::Template);
+        assert_eq!(empty.len(), (ICON_SIZE * ICON_SIZE * 4) as usize);
+        assert_eq!(pixel(&empty, bottom.0, bottom.1)[3], 0);
+        assert_eq!(pixel(&empty, 0, 0)[3], 0);
+        // 枠線は常に描かれる
+        assert_eq!(pixel(&empty, 16, 27)[3], 255);
+
+        let one = render_progress_icon(1, IconStyle::Template);
+        assert_eq!(pixel(&one, bottom.0, bottom.1), [0, 0, 0, 255]);
+        assert_eq!(pixel(&one, right.0, right.1)[3], 0);
+
+        let two = render_progress_icon(2, IconStyle::Dark);
+        assert_eq!(pixel(&two, right.0, right.1), [0xF9, 0xFA, 0xFB, 255]);
+    }
+}
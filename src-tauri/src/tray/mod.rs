@@ -1,59 +1,207 @@
+mod icon;
+
+use std::sync::{Arc, Mutex};
+use chrono::Local;
 use tauri::{
-    AppHandle, Manager, PhysicalPosition, PhysicalSize, Runtime,
-    menu::{Menu, MenuItem},
+    AppHandle, Emitter, Listener, Manager, PhysicalPosition, PhysicalSize, Runtime,
+    image::Image,
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem},
     tray::{TrayIconBuilder, TrayIconEvent},
 };
+use icon::{render_progress_icon, IconStyle, ICON_SIZE};
+use crate::commands::goals::{get_week_start_setting, toggle_goal};
+use crate::commands::periods::period_range;
 use crate::db::Database;
+use crate::i18n::Locale;
+use crate::models::{Goal, GoalLevel};
+use crate::notifications::{NotificationCenter, ReminderAction, ReminderKind};
+
+/// Menu item ids of goal check items are this prefix plus the goal id
+const GOAL_ITEM_PREFIX: &str = "goal:";
 
-// Get localized menu text based on language setting
-fn get_menu_text(language: &str, key: &str) -> &'static str {
-    match (language, key) {
-        ("en", "show_floating") => "Show Floating Window",
-        ("en", "quit") => "Quit",
-        (_, "show_floating") => "フローティングウィンドウを表示",
-        (_, "quit") => "終了",
-        _ => "Unknown", // Fallback for unknown keys
+/// Menu item ids of reminder actions are this prefix plus the action
+const REMINDER_ITEM_PREFIX: &str = "reminder:";
+
+fn reminder_action_label(action: ReminderAction) -> &'static str {
+    match action {
+        ReminderAction::ReflectNow => "reminder.reflectNow",
+        ReminderAction::PlanNow => "reminder.planNow",
+        ReminderAction::Snooze => "reminder.snooze",
+        ReminderAction::SkipWeek => "reminder.skipWeek",
     }
 }
 
-pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
-    // Get current language setting from database
+/// Goals of the current period of `level`, oldest first
+fn current_goals(db: &Database, level: GoalLevel) -> Vec<Goal> {
+    let (start, end) = period_range(level, &Local::now(), get_week_start_setting(db));
+    db.get_goals_in_range(level.as_str(), start.timestamp_millis(), end.timestamp_millis())
+        .unwrap_or_default()
+}
+
+/// Completed and total goals of the current day, week and month
+fn progress(db: &Database) -> [(GoalLevel, usize, usize); 3] {
+    [GoalLevel::Daily, GoalLevel::Weekly, GoalLevel::Monthly].map(|level| {
+        let goals = current_goals(db, level);
+        (level, goals.iter().filter(|g| g.is_completed).count(), goals.len())
+    })
+}
+
+// Tooltip like "Today 2/3 · Week 1/3 · Month 0/3"
+fn progress_tooltip(locale: &Locale, progress: &[(GoalLevel, usize, usize)]) -> String {
+    progress
+        .iter()
+        .map(|&(level, completed, total)| {
+            locale.t_with(
+                &format!("tray.progress.{}", level.as_str()),
+                &[("completed", completed.to_string()), ("total", total.to_string())],
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+// macOS tints template images itself; elsewhere pick the variant that
+// contrasts with the system theme
+fn icon_style<R: Runtime>(app: &AppHandle<R>) -> IconStyle {
+    #[cfg(target_os = "macos")]
+    {
+        let _ = app;
+        IconStyle::Template
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    match app.get_webview_window("main").and_then(|window| window.theme().ok()) {
+        Some(tauri::Theme::Dark) => IconStyle::Dark,
+        _ => IconStyle::Light,
+    }
+}
+
+// Triangle with one segment filled per goal completed today
+fn progress_icon(completed_today: usize, style: IconStyle) -> Image<'static> {
+    Image::new_owned(render_progress_icon(completed_today, style), ICON_SIZE, ICON_SIZE)
+}
+
+// Build the tray menu with localized text: today's (and optionally this
+// week's) goals as check items, shortcuts into the popover, then app items
+fn build_menu<R: Runtime>(app: &AppHandle<R>, locale: &Locale) -> tauri::Result<Menu<R>> {
     let db: tauri::State<Database> = app.state();
-    let language = db.get_setting("language")
-        .unwrap_or_else(|_| "system".to_string());
-
-    // Determine effective language
-    let effective_lang = if language == "system" {
-        // Default to Japanese for system language
-        // In practice, you might want to detect the system language
-        "ja"
-    } else {
-        language.as_str()
-    };
-
-    // Create menu items with localized text
+    let menu = Menu::new(app)?;
+
+    let mut levels = vec![(GoalLevel::Daily, "tray.today")];
+    if db.get_setting("tray_show_weekly_goals").unwrap_or_default() == "true" {
+        levels.push((GoalLevel::Weekly, "tray.thisWeek"));
+    }
+    for (level, label) in levels {
+        menu.append(&MenuItem::new(app, locale.t(label), false, None::<&str>)?)?;
+
+        let goals = current_goals(&db, level);
+        if goals.is_empty() {
+            menu.append(&MenuItem::new(app, locale.t("tray.noGoals"), false, None::<&str>)?)?;
+        }
+        for goal in goals {
+            menu.append(&CheckMenuItem::with_id(
+                app,
+                format!("{}{}", GOAL_ITEM_PREFIX, goal.id),
+                &goal.title,
+                true,
+                goal.is_completed,
+                None::<&str>
+            )?)?;
+        }
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+
+    // 未対応のリマインダーは通知を見逃しても、ここから選べる
+    let open_reminder = app
+        .try_state::<Arc<NotificationCenter>>()
+        .and_then(|notifications| notifications.open_reminder());
+    if let Some(kind) = open_reminder {
+        let (title, open) = match kind {
+            ReminderKind::Reflection => ("reminder.reflectionTitle", ReminderAction::ReflectNow),
+            ReminderKind::Planning => ("reminder.planningTitle", ReminderAction::PlanNow),
+        };
+        menu.append(&MenuItem::new(app, locale.t(title), false, None::<&str>)?)?;
+        for action in [open, ReminderAction::Snooze, ReminderAction::SkipWeek] {
+            menu.append(&MenuItem::with_id(
+                app,
+                format!("{}{}", REMINDER_ITEM_PREFIX, action.as_str()),
+                locale.t(reminder_action_label(action)),
+                true,
+                None::<&str>
+            )?)?;
+        }
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+
+    for (id, key) in [
+        ("add_goal", "tray.addGoal"),
+        ("reflect", "tray.reflect"),
+        ("history", "tray.history"),
+        ("settings", "tray.settings"),
+    ] {
+        menu.append(&MenuItem::with_id(app, id, locale.t(key), true, None::<&str>)?)?;
+    }
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+
     let show_floating_item = MenuItem::with_id(
         app,
         "show_floating",
-        get_menu_text(effective_lang, "show_floating"),
+        locale.t("tray.showFloating"),
+        true,
+        None::<&str>
+    )?;
+    let undo_item = MenuItem::with_id(
+        app,
+        "undo",
+        locale.t("tray.undo"),
+        true,
+        None::<&str>
+    )?;
+    let redo_item = MenuItem::with_id(
+        app,
+        "redo",
+        locale.t("tray.redo"),
         true,
         None::<&str>
     )?;
     let quit_item = MenuItem::with_id(
         app,
         "quit",
-        get_menu_text(effective_lang, "quit"),
+        locale.t("tray.quit"),
         true,
         None::<&str>
     )?;
+    let separator = PredefinedMenuItem::separator(app)?;
+
+    menu.append_items(&[&show_floating_item, &undo_item, &redo_item, &separator, &quit_item])?;
+    Ok(menu)
+}
+
+/// Show the popover and switch it to `target` ("add-goal", "goals", "reflection",
+/// "history" or "settings")
+pub fn open_popover<R: Runtime>(app: &AppHandle<R>, target: &str) {
+    if let Some(window) = app.get_webview_window("popover") {
+        let _ = window.show();
+        let _ = window.set_focus();
+        let _ = window.emit_to("popover", "tray-navigate", target);
+    }
+}
+
+pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+    // Get current language setting from database ("system" follows the OS)
+    let db: tauri::State<Database> = app.state();
+    let locale = Locale::for_db(&db);
 
     // Create menu
-    let menu = Menu::with_items(app, &[&show_floating_item, &quit_item])?;
+    let menu = build_menu(app, &locale)?;
 
     // Create tray icon
+    let progress = progress(&db);
+    let style = icon_style(app);
     let tray = TrayIconBuilder::new()
-        .icon(app.default_window_icon().unwrap().clone())
-        .tooltip("Trivyn")
+        .icon(progress_icon(progress[0].1, style))
+        .icon_as_template(style == IconStyle::Template)
+        .tooltip(progress_tooltip(&locale, &progress))
         .menu(&menu)
         .show_menu_on_left_click(false) // Disable menu on left click
         .on_menu_event(|app, event| {
@@ -64,10 +212,36 @@ pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
                         let _ = window.set_focus();
                     }
                 }
+                "undo" => {
+                    if let Err(e) = crate::undo::undo(app) {
+                        eprintln!("Undo from tray failed: {}", e);
+                    }
+                }
+                "redo" => {
+                    if let Err(e) = crate::undo::redo(app) {
+                        eprintln!("Redo from tray failed: {}", e);
+                    }
+                }
+                "add_goal" => crate::quick_add::show(app),
+                "reflect" => open_popover(app, "reflection"),
+                "history" => open_popover(app, "history"),
+                "settings" => open_popover(app, "settings"),
                 "quit" => {
                     app.exit(0);
                 }
-                _ => {}
+                id => {
+                    if let Some(goal_id) = id.strip_prefix(GOAL_ITEM_PREFIX) {
+                        if let Err(e) = toggle_goal(app, goal_id) {
+                            eprintln!("Toggle from tray failed: {}", e);
+                        }
+                    }
+                    let action = id.strip_prefix(REMINDER_ITEM_PREFIX).and_then(ReminderAction::from_str);
+                    if let Some(action) = action {
+                        if let Err(e) = crate::notifications::handle_action(app, action) {
+                            eprintln!("Reminder action from tray failed: {}", e);
+                        }
+                    }
+                }
             }
         })
         .on_tray_icon_event(move |tray, event| {
@@ -154,13 +328,37 @@ pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
         .build(app)?;
 
     // Store the tray icon ID in app state
-    app.manage(TrayIconId(tray.id().clone()));
+    app.manage(TrayIconId(tray.id().clone(), Mutex::new(locale.language().to_string())));
+
+    // Rebuild the goal items and redraw the progress whenever goals change,
+    // a new day starts, a reminder is shown or handled, or the theme changes
+    for event in ["goals-updated", "period-rolled-over", "reminders-updated", "theme-changed"] {
+        let handle = app.clone();
+        app.listen_any(event, move |_| {
+            if let Err(e) = refresh_tray_menu(&handle) {
+                eprintln!("Failed to refresh tray menu: {}", e);
+            }
+        });
+    }
+
+    // The icon variant follows the window theme, which also changes when
+    // the OS switches between light and dark without any app event
+    if let Some(window) = app.get_webview_window("main") {
+        let handle = app.clone();
+        window.on_window_event(move |event| {
+            if let tauri::WindowEvent::ThemeChanged(_) = event {
+                if let Err(e) = refresh_tray_menu(&handle) {
+                    eprintln!("Failed to refresh tray menu: {}", e);
+                }
+            }
+        });
+    }
 
     Ok(())
 }
 
-// Wrapper for TrayIconId to store in app state
-struct TrayIconId(tauri::tray::TrayIconId);
+// Wrapper for TrayIconId to store in app state, with the menu language
+struct TrayIconId(tauri::tray::TrayIconId, Mutex<String>);
 
 impl TrayIconId {
     fn get(&self) -> &tauri::tray::TrayIconId {
@@ -168,35 +366,36 @@ impl TrayIconId {
     }
 }
 
-// Update tray menu with new language
+// Update tray menu, icon and tooltip with new language
 pub fn update_tray_menu<R: Runtime>(app: &AppHandle<R>, language: &str) -> tauri::Result<()> {
     // Get the stored tray icon ID
     let tray_id = app.state::<TrayIconId>();
+    *tray_id.1.lock().unwrap() = language.to_string();
 
     // Get the tray icon using the stored ID
     if let Some(tray) = app.tray_by_id(tray_id.get()) {
-        // Create new menu items with updated text
-        let show_floating_item = MenuItem::with_id(
-            app,
-            "show_floating",
-            get_menu_text(language, "show_floating"),
-            true,
-            None::<&str>
-        )?;
-        let quit_item = MenuItem::with_id(
-            app,
-            "quit",
-            get_menu_text(language, "quit"),
-            true,
-            None::<&str>
-        )?;
-
-        // Create new menu
-        let menu = Menu::with_items(app, &[&show_floating_item, &quit_item])?;
+        // Create new menu with updated text
+        let locale = Locale::from_setting(language);
+        let menu = build_menu(app, &locale)?;
 
         // Update the tray menu
         tray.set_menu(Some(menu))?;
+
+        // Redraw the progress
+        let db: tauri::State<Database> = app.state();
+        let progress = progress(&db);
+        let style = icon_style(app);
+        tray.set_icon(Some(progress_icon(progress[0].1, style)))?;
+        tray.set_icon_as_template(style == IconStyle::Template)?;
+        tray.set_tooltip(Some(progress_tooltip(&locale, &progress)))?;
     }
 
     Ok(())
 }
+
+/// Rebuild the tray menu and progress in the current language, e.g. after
+/// goals changed
+pub fn refresh_tray_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+    let language = app.state::<TrayIconId>().1.lock().unwrap().clone();
+    update_tray_menu(app, &language)
+}
@@ -0,0 +1,376 @@
+use std::sync::Mutex;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime};
+use crate::commands::goals::{broadcast_goals_updated, ensure_period_has_room};
+use rusqlite::Connection;
+use crate::db::{goals, reflections, Database};
+use crate::models::{Goal, Reflection};
+
+/// How many mutations can be undone
+const MAX_HISTORY: usize = 100;
+
+/// User-facing kind of mutation, used for "Undo <action>" labels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UndoAction {
+    AddGoal,
+    ToggleGoal,
+    EditGoal,
+    DeleteGoal,
+    RestoreGoal,
+    SaveReflection,
+}
+
+/// State of a single record before and after a mutation.
+/// `None` means the record did not exist.
+#[derive(Debug, Clone)]
+pub enum Change {
+    Goal { before: Option<Goal>, after: Option<Goal> },
+    Reflection { before: Option<Reflection>, after: Option<Reflection> },
+}
+
+#[derive(Debug, Clone)]
+pub struct UndoEntry {
+    pub action: UndoAction,
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoState {
+    pub can_undo: bool,
+    pub can_redo: bool,
+    pub undo_action: Option<UndoAction>,
+    pub redo_action: Option<UndoAction>,
+}
+
+#[derive(Default)]
+struct Stacks {
+    undo: Vec<UndoEntry>,
+    redo: Vec<UndoEntry>,
+}
+
+/// Backend-maintained history of goal and reflection mutations.
+/// Every entry stores full snapshots, so undo and redo simply write back
+/// the "before" or "after" state.
+#[derive(Default)]
+pub struct UndoStack {
+    stacks: Mutex<Stacks>,
+}
+
+#[derive(Clone, Copy)]
+enum Direction {
+    Undo,
+    Redo,
+}
+
+impl UndoStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a completed mutation. Any redo history is discarded.
+    pub fn record(&self, action: UndoAction, changes: Vec<Change>) {
+        let mut stacks = self.stacks.lock().unwrap();
+        stacks.undo.push(UndoEntry { action, changes });
+        if stacks.undo.len() > MAX_HISTORY {
+            stacks.undo.remove(0);
+        }
+        stacks.redo.clear();
+    }
+
+    pub fn state(&self) -> UndoState {
+        let stacks = self.stacks.lock().unwrap();
+        UndoState {
+            can_undo: !stacks.undo.is_empty(),
+            can_redo: !stacks.redo.is_empty(),
+            undo_action: stacks.undo.last().map(|e| e.action),
+            redo_action: stacks.redo.last().map(|e| e.action),
+        }
+    }
+
+    pub fn undo(&self, db: &Database) -> Result<UndoState, String> {
+        self.step(db, Direction::Undo)
+    }
+
+    pub fn redo(&self, db: &Database) -> Result<UndoState, String> {
+        self.step(db, Direction::Redo)
+    }
+
+    fn step(&self, db: &Database, direction: Direction) -> Result<UndoState, String> {
+        let mut stacks = self.stacks.lock().unwrap();
+        let source = match direction {
+            Direction::Undo => &mut stacks.undo,
+            Direction::Redo => &mut stacks.redo,
+        };
+        let entry = source.last().cloned().ok_or_else(|| match direction {
+            Direction::Undo => "Nothing to undo".to_string(),
+            Direction::Redo => "Nothing to redo".to_string(),
+        })?;
+
+        // A record changed by something the history does not know about (a
+        // purge, another window) can never be restored cleanly, so the entry
+        // is dropped. Otherwise it would block every older entry for good.
+        if let Err(e) = entry.changes.iter().try_for_each(|change| check_unchanged(db, change, direction)) {
+            source.pop();
+            return Err(e);
+        }
+        // A full period is only temporary; keep the entry for a later try
+        for change in &entry.changes {
+            check_room(db, change, direction)?;
+        }
+        db.in_transaction(|conn| {
+            entry.changes.iter().try_for_each(|change| apply_change(conn, change, direction))
+        })
+        .map_err(|e| e.to_string())?;
+
+        source.pop();
+        match direction {
+            Direction::Undo => stacks.redo.push(entry),
+            Direction::Redo => stacks.undo.push(entry),
+        }
+        drop(stacks);
+
+        Ok(self.state())
+    }
+}
+
+/// Make sure the record still looks the way the entry left it
+fn check_unchanged(db: &Database, change: &Change, direction: Direction) -> Result<(), String> {
+    match change {
+        Change::Goal { before, after } => {
+            let (expected, target) = match direction {
+                Direction::Undo => (after, before),
+                Direction::Redo => (before, after),
+            };
+            let id = expected.as_ref().or(target.as_ref()).map(|g| g.id.as_str()).unwrap_or_default();
+            let current = db.get_goal(id).map_err(|e| e.to_string())?;
+            if current != *expected {
+                return Err("Goal was changed elsewhere and can no longer be undone".to_string());
+            }
+        }
+        Change::Reflection { before, after } => {
+            let (expected, target) = match direction {
+                Direction::Undo => (after, before),
+                Direction::Redo => (before, after),
+            };
+            let Some(key) = expected.as_ref().or(target.as_ref()) else { return Ok(()) };
+            let current = db.get_reflection(key.level.as_str(), &key.period_key)
+                .map_err(|e| e.to_string())?;
+            if current != *expected {
+                return Err("Reflection was changed elsewhere and can no longer be undone".to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Make sure bringing a goal back does not exceed the per-period limit
+fn check_room(db: &Database, change: &Change, direction: Direction) -> Result<(), String> {
+    let Change::Goal { before, after } = change else { return Ok(()) };
+    let (current, target) = match direction {
+        Direction::Undo => (after, before),
+        Direction::Redo => (before, after),
+    };
+
+    let is_active = |g: &Option<Goal>| g.as_ref().is_some_and(|g| g.deleted_at.is_none());
+    if let Some(goal) = target.as_ref().filter(|_| is_active(target) && !is_active(current)) {
+        let period_dt = DateTime::from_timestamp_millis(goal.period_start)
+            .ok_or("Invalid period_start timestamp")?
+            .with_timezone(&Local);
+        ensure_period_has_room(db, goal.level, &period_dt)?;
+    }
+    Ok(())
+}
+
+fn apply_change(conn: &Connection, change: &Change, direction: Direction) -> anyhow::Result<()> {
+    match change {
+        Change::Goal { before, after } => {
+            let (from, to) = match direction {
+                Direction::Undo => (after, before),
+                Direction::Redo => (before, after),
+            };
+            match (from, to) {
+                (_, Some(goal)) => goals::write_goal_snapshot(conn, goal),
+                (Some(goal), None) => goals::remove_goal(conn, &goal.id),
+                (None, None) => Ok(()),
+            }
+        }
+        Change::Reflection { before, after } => {
+            let (from, to) = match direction {
+                Direction::Undo => (after, before),
+                Direction::Redo => (before, after),
+            };
+            match (from, to) {
+                (_, Some(reflection)) => reflections::write_reflection_snapshot(conn, reflection),
+                (Some(reflection), None) => {
+                    reflections::delete_reflection(conn, reflection.level.as_str(), &reflection.period_key)
+                }
+                (None, None) => Ok(()),
+            }
+        }
+    }
+}
+
+/// Undo the latest mutation and refresh every window.
+/// Shared by the `undo` command and the tray menu.
+pub fn undo<R: Runtime>(app: &AppHandle<R>) -> Result<UndoState, String> {
+    let db = app.state::<Database>();
+    let state = app.state::<UndoStack>().undo(&db)?;
+    broadcast_goals_updated(app);
+    Ok(state)
+}
+
+/// Redo the latest undone mutation and refresh every window
+pub fn redo<R: Runtime>(app: &AppHandle<R>) -> Result<UndoState, String> {
+    let db = app.state::<Database>();
+    let state = app.state::<UndoStack>().redo(&db)?;
+    broadcast_goals_updated(app);
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::GoalLevel;
+
+    fn test_db() -> Database {
+        let db = Database::new(":memory:".into()).unwrap();
+        db.migrate().unwrap();
+        db
+    }
+
+    fn add(db: &Database, history: &UndoStack, title: &str) -> Goal {
+        let goal = Goal::new(title.to_string(), GoalLevel::Daily, Local::now().timestamp_millis(), None);
+        db.add_goal(&goal).unwrap();
+        history.record(UndoAction::AddGoal, vec![Change::Goal { before: None, after: Some(goal.clone()) }]);
+        goal
+    }
+
+    fn delete(db: &Database, history: &UndoStack, goal: &Goal) {
+        let before = db.get_goal(&goal.id).unwrap();
+        db.delete_goal(&goal.id).unwrap();
+        let after = db.get_goal(&goal.id).unwrap();
+        history.record(UndoAction::DeleteGoal, vec![Change::Goal { before, after }]);
+    }
+
+    #[test]
+    fn test_undo_redo_add_toggle_delete() {
+        let db = test_db();
+        let history = UndoStack::new();
+        let goal = add(&db, &history, "Call bank");
+
+        let before = db.get_goal(&goal.id).unwrap();
+        let toggled = db.toggle_goal_completion(&goal.id).unwrap();
+        history.record(UndoAction::ToggleGoal, vec![Change::Goal { before, after: Some(toggled) }]);
+        delete(&db, &history, &goal);
+        assert_eq!(history.state().undo_action, Some(UndoAction::DeleteGoal));
+
+        // 削除 → 完了 → 追加 の順に戻る
+        history.undo(&db).unwrap();
+        assert!(db.get_goal(&goal.id).unwrap().unwrap().deleted_at.is_none());
+        history.undo(&db).unwrap();
+        assert!(!db.get_goal(&goal.id).unwrap().unwrap().is_completed);
+        let state = history.undo(&db).unwrap();
+        assert!(db.get_goal(&goal.id).unwrap().is_none());
+        assert!(!state.can_undo);
+        assert_eq!(state.redo_action, Some(UndoAction::AddGoal));
+        assert!(history.undo(&db).is_err());
+
+        history.redo(&db).unwrap();
+        history.redo(&db).unwrap();
+        let state = history.redo(&db).unwrap();
+        let redone = db.get_goal(&goal.id).unwrap().unwrap();
+        assert!(redone.is_completed && redone.deleted_at.is_some());
+        assert!(!state.can_redo);
+
+        // 新しい操作で redo 履歴は消える
+        history.undo(&db).unwrap();
+        add(&db, &history, "Write RFC");
+        assert!(!history.state().can_redo);
+    }
+
+    #[test]
+    fn test_undo_redo_reflection_save() {
+        let db = test_db();
+        let history = UndoStack::new();
+        let reflection = |insight: &str| {
+            Reflection::new(GoalLevel::Weekly, "2026-W42".into(), Some(insight.into()), None, None)
+        };
+
+        let first = db.save_reflection(&reflection("first")).unwrap();
+        history.record(UndoAction::SaveReflection, vec![Change::Reflection { before: None, after: Some(first.clone()) }]);
+        let second = db.save_reflection(&reflection("second")).unwrap();
+        history.record(UndoAction::SaveReflection, vec![Change::Reflection {
+            before: Some(first.clone()),
+            after: Some(second.clone()),
+        }]);
+
+        history.undo(&db).unwrap();
+        assert_eq!(db.get_reflection("weekly", "2026-W42").unwrap(), Some(first.clone()));
+        history.undo(&db).unwrap();
+        assert_eq!(db.get_reflection("weekly", "2026-W42").unwrap(), None);
+        history.redo(&db).unwrap();
+        history.redo(&db).unwrap();
+        assert_eq!(db.get_reflection("weekly", "2026-W42").unwrap(), Some(second));
+    }
+
+    #[test]
+    fn test_changed_elsewhere_drops_entry() {
+        let db = test_db();
+        let history = UndoStack::new();
+        let kept = add(&db, &history, "Write RFC");
+        let purged = add(&db, &history, "Call bank");
+        delete(&db, &history, &purged);
+
+        // 履歴に残らない完全削除のあとは戻せないが、古い履歴は引き続き使える
+        db.purge_goal(&purged.id).unwrap();
+        assert!(history.undo(&db).unwrap_err().contains("changed elsewhere"));
+        assert_eq!(history.state().undo_action, Some(UndoAction::AddGoal));
+        assert!(history.undo(&db).is_err());
+        history.undo(&db).unwrap();
+        assert!(db.get_goal(&kept.id).unwrap().is_none());
+        assert!(!history.state().can_undo);
+    }
+
+    #[test]
+    fn test_full_period_keeps_entry() {
+        let db = test_db();
+        let history = UndoStack::new();
+        let goals: Vec<Goal> = ["A", "B", "C"].iter().map(|t| add(&db, &history, t)).collect();
+        delete(&db, &history, &goals[0]);
+        let filler = Goal::new("D".into(), GoalLevel::Daily, Local::now().timestamp_millis(), None);
+        db.add_goal(&filler).unwrap();
+
+        // 枠が空けば同じ履歴で戻せる
+        assert!(history.undo(&db).is_err());
+        assert_eq!(history.state().undo_action, Some(UndoAction::DeleteGoal));
+        db.delete_goal(&filler.id).unwrap();
+        history.undo(&db).unwrap();
+        assert!(db.get_goal(&goals[0].id).unwrap().unwrap().deleted_at.is_none());
+    }
+
+    #[test]
+    fn test_entry_is_applied_atomically() {
+        let db = test_db();
+        let history = UndoStack::new();
+        let goal = Goal::new("Call bank".into(), GoalLevel::Daily, Local::now().timestamp_millis(), None);
+        db.add_goal(&goal).unwrap();
+        let other = db.save_reflection(&Reflection::new(GoalLevel::Monthly, "2026-10".into(), None, None, None)).unwrap();
+
+        // 2 つ目の変更は id が既存の振り返りと衝突して失敗する
+        let clashing = Reflection {
+            id: other.id,
+            ..Reflection::new(GoalLevel::Weekly, "2026-W42".into(), Some("x".into()), None, None)
+        };
+        history.record(UndoAction::AddGoal, vec![
+            Change::Goal { before: None, after: Some(goal.clone()) },
+            Change::Reflection { before: Some(clashing), after: None },
+        ]);
+
+        assert!(history.undo(&db).is_err());
+        assert!(db.get_goal(&goal.id).unwrap().is_some());
+        assert!(history.state().can_undo);
+    }
+}
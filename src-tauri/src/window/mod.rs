@@ -194,6 +194,30 @@ pub fn setup_popover_window<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()>
     Ok(())
 }
 
+/// The quick-add window works like a launcher: it hides when it loses focus
+/// and closing only hides it
+pub fn setup_quick_add_window<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+    if let Some(window) = app.get_webview_window("quick-add") {
+        window.set_always_on_top(true)?;
+
+        let window_clone = window.clone();
+        window.on_window_event(move |event| {
+            match event {
+                tauri::WindowEvent::Focused(false) => {
+                    let _ = window_clone.hide();
+                }
+                tauri::WindowEvent::CloseRequested { api, .. } => {
+                    api.prevent_close();
+                    let _ = window_clone.hide();
+                }
+                _ => {}
+            }
+        });
+    }
+
+    Ok(())
+}
+
 #[derive(serde::Serialize, serde::Deserialize)]
 struct WindowPosition {
     x: f64,
@@ -1,11 +1,13 @@
 import { useEffect } from "react";
 import FloatingWindow from "./components/floating/FloatingWindow";
 import MenuBarPopover from "./components/popover/MenuBarPopover";
+import QuickAddWindow from "./components/quickadd/QuickAddWindow";
 import './i18n';
 
 function App() {
   // Determine which view to show based on window label
   const isPopover = window.location.search.includes('popover');
+  const isQuickAdd = window.location.search.includes('quick-add');
 
   // Ensure containers fill window completely
   useEffect(() => {
@@ -24,7 +26,7 @@ function App() {
 
   return (
     <>
-      {isPopover ? <MenuBarPopover /> : <FloatingWindow />}
+      {isQuickAdd ? <QuickAddWindow /> : isPopover ? <MenuBarPopover /> : <FloatingWindow />}
     </>
   );
 }
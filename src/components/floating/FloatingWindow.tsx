@@ -2,7 +2,7 @@ import { useState, useEffect, useRef } from 'react';
 import { useTranslation } from 'react-i18next';
 import { useGoalStore } from '../../store/goalStore';
 import { useSettingsStore } from '../../store/settingsStore';
-import { GoalLevel } from '../../types';
+import { GoalLevel, PeriodRolledOverEvent } from '../../types';
 import LevelSwitcher from './LevelSwitcher';
 import NumberedGoalRow from './NumberedGoalRow';
 import AddGoalField from './AddGoalField';
@@ -40,7 +40,6 @@ export default function FloatingWindow() {
   } = useGoalStore();
   const { loadSettings, weekStart } = useSettingsStore();
   const containerRef = useRef<HTMLDivElement>(null);
-  const lastCheckDateRef = useRef<string>(new Date().toDateString());
 
   // Sync weekStart from settings to goal store
   useEffect(() => {
@@ -61,18 +60,34 @@ export default function FloatingWindow() {
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, []);
 
-  // Check for date changes and reload goals
+  // Undo/redo goal changes with Cmd/Ctrl+Z and Shift+Cmd/Ctrl+Z.
+  // Text fields keep their native undo behaviour
   useEffect(() => {
-    const checkInterval = setInterval(() => {
-      const currentDate = new Date().toDateString();
-      if (currentDate !== lastCheckDateRef.current) {
-        console.log('[FloatingWindow] Date changed, reloading goals');
-        lastCheckDateRef.current = currentDate;
-        loadGoals();
+    const handleKeyDown = (e: KeyboardEvent) => {
+      if (!(e.metaKey || e.ctrlKey) || e.key.toLowerCase() !== 'z') return;
+      const target = e.target as HTMLElement | null;
+      if (target && (target.tagName === 'INPUT' || target.tagName === 'TEXTAREA')) return;
+      e.preventDefault();
+      if (e.shiftKey) {
+        useGoalStore.getState().redo();
+      } else {
+        useGoalStore.getState().undo();
       }
-    }, 60000); // Check every minute
+    };
+    window.addEventListener('keydown', handleKeyDown);
+    return () => window.removeEventListener('keydown', handleKeyDown);
+  }, []);
 
-    return () => clearInterval(checkInterval);
+  // The backend fires this at the start of each new day, week or month,
+  // and after sleep or clock changes
+  useEffect(() => {
+    const unlisten = listen<PeriodRolledOverEvent>('period-rolled-over', (event) => {
+      console.log('[FloatingWindow] Period rolled over, reloading goals:', event.payload);
+      loadGoals();
+    });
+    return () => {
+      unlisten.then(fn => fn());
+    };
   }, [loadGoals]);
 
   // Listen for language changes from other windows
@@ -3,11 +3,13 @@ import { useTranslation } from 'react-i18next';
 import { useGoalStore } from '../../store/goalStore';
 import { useReflectionStore } from '../../store/reflectionStore';
 import { useSettingsStore } from '../../store/settingsStore';
-import { Goal, GoalLevel } from '../../types';
+import { Goal, GoalLevel, PopoverRoute } from '../../types';
 import { getPeriodKey, getWeekNumber, isSamePeriod } from '../../utils/periods';
 
 interface HistoryViewProps {
   onHeightChange?: (height: number) => void;
+  route?: Extract<PopoverRoute, { view: 'history' }> | null;
+  onRouteConsumed?: () => void;
 }
 
 function getDaysInMonth(year: number, month: number, weekStart: number): Date[] {
@@ -108,7 +110,7 @@ function getLevelFromSelection(selection: Selection): GoalLevel | null {
   }
 }
 
-export default function HistoryView({ onHeightChange }: HistoryViewProps) {
+export default function HistoryView({ onHeightChange, route, onRouteConsumed }: HistoryViewProps) {
   const { t, i18n } = useTranslation();
   const { goals } = useGoalStore();
   const { loadReflection, getReflection } = useReflectionStore();
@@ -173,6 +175,15 @@ export default function HistoryView({ onHeightChange }: HistoryViewProps) {
     return getReflection(level, periodKey);
   }, [selection, weekStart, getReflection]);
 
+  // Select the day a trivyn://history link points at
+  useEffect(() => {
+    if (!route) return;
+    const date = new Date(route.date);
+    setCurrentDate(date);
+    setSelection({ type: 'date', date });
+    onRouteConsumed?.();
+  }, [route, onRouteConsumed]);
+
   const goToPreviousMonth = () => {
     setCurrentDate(new Date(year, month - 1, 1));
     setSelection(null);
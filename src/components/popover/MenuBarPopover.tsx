@@ -2,10 +2,11 @@ import { useState, useEffect, useRef } from 'react';
 import { useTranslation } from 'react-i18next';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
+import { onAction, registerActionTypes } from '@tauri-apps/plugin-notification';
 import { useGoalStore } from '../../store/goalStore';
 import { useSettingsStore } from '../../store/settingsStore';
 import { useUpdateStore } from '../../store/updateStore';
-import { GoalLevel } from '../../types';
+import { Goal, GoalLevel, PeriodRolledOverEvent, PopoverRoute } from '../../types';
 import HistoryView from './HistoryView';
 import ReflectionView, { PeriodChangeEvent } from './ReflectionView';
 import SettingsView from './SettingsView';
@@ -84,6 +85,9 @@ export default function MenuBarPopover() {
   const [historyHeight, setHistoryHeight] = useState(720);
   const [settingsHeight, setSettingsHeight] = useState(650);
   const [reflectionTrigger, setReflectionTrigger] = useState<PeriodChangeEvent | null>(null);
+  const [popoverRoute, setPopoverRoute] = useState<PopoverRoute | null>(null);
+  // Goal a trivyn://toggle link asked about, toggled only once the user agrees
+  const [toggleRequest, setToggleRequest] = useState<Goal | null>(null);
   // キャリーオーバー提案の却下は期間キー単位で永続化（同じ期間内は再表示しない）
   const [carryOverDismissed, setCarryOverDismissed] = useState<Record<GoalLevel, string | null>>(() => ({
     daily: localStorage.getItem('trivyn.carryOverDismissed.daily'),
@@ -92,7 +96,6 @@ export default function MenuBarPopover() {
   }));
   const containerRef = useRef<HTMLDivElement>(null);
   const goalsContentRef = useRef<HTMLDivElement>(null);
-  const lastCheckDateRef = useRef<string>(new Date().toDateString());
   const {
     goals,
     loadGoals,
@@ -113,11 +116,15 @@ export default function MenuBarPopover() {
   const { loadSettings, weekStart } = useSettingsStore();
   const { status: updateStatus, version: updateVersion, checkForUpdate, installUpdate } = useUpdateStore();
 
-  // Check for app updates on startup and periodically (every 6 hours)
+  // Check for app updates on startup and whenever the backend scheduler says one is due
   useEffect(() => {
     checkForUpdate();
-    const interval = setInterval(checkForUpdate, 6 * 60 * 60 * 1000);
-    return () => clearInterval(interval);
+    const unlisten = listen('update-check-due', () => {
+      checkForUpdate();
+    });
+    return () => {
+      unlisten.then(fn => fn());
+    };
   }, [checkForUpdate]);
 
   // Sync weekStart from settings to goal store
@@ -139,18 +146,34 @@ export default function MenuBarPopover() {
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, []);
 
-  // Check for date changes and reload goals
+  // Undo/redo goal changes with Cmd/Ctrl+Z and Shift+Cmd/Ctrl+Z.
+  // Text fields keep their native undo behaviour
   useEffect(() => {
-    const checkInterval = setInterval(() => {
-      const currentDate = new Date().toDateString();
-      if (currentDate !== lastCheckDateRef.current) {
-        console.log('[MenuBarPopover] Date changed, reloading goals');
-        lastCheckDateRef.current = currentDate;
-        loadGoals();
+    const handleKeyDown = (e: KeyboardEvent) => {
+      if (!(e.metaKey || e.ctrlKey) || e.key.toLowerCase() !== 'z') return;
+      const target = e.target as HTMLElement | null;
+      if (target && (target.tagName === 'INPUT' || target.tagName === 'TEXTAREA')) return;
+      e.preventDefault();
+      if (e.shiftKey) {
+        useGoalStore.getState().redo();
+      } else {
+        useGoalStore.getState().undo();
       }
-    }, 60000); // Check every minute
+    };
+    window.addEventListener('keydown', handleKeyDown);
+    return () => window.removeEventListener('keydown', handleKeyDown);
+  }, []);
 
-    return () => clearInterval(checkInterval);
+  // The backend fires this at the start of each new day, week or month,
+  // and after sleep or clock changes
+  useEffect(() => {
+    const unlisten = listen<PeriodRolledOverEvent>('period-rolled-over', (event) => {
+      console.log('[MenuBarPopover] Period rolled over, reloading goals:', event.payload);
+      loadGoals();
+    });
+    return () => {
+      unlisten.then(fn => fn());
+    };
   }, [loadGoals]);
 
   // Reload goals when window becomes visible
@@ -181,11 +204,11 @@ export default function MenuBarPopover() {
   useEffect(() => {
     const setupReflectionListener = async () => {
       const unlisten = await listen<PeriodChangeEvent>('reflection-prompt-trigger', (event) => {
-        const { has_weekly_change, has_monthly_change } = event.payload;
+        const { has_weekly_change, has_monthly_change, has_daily_review } = event.payload;
 
         console.log('[MenuBarPopover] Received reflection-prompt-trigger event:', event.payload);
 
-        if (has_weekly_change || has_monthly_change) {
+        if (has_weekly_change || has_monthly_change || has_daily_review) {
           setBottomTab('reflection');
           setReflectionTrigger(event.payload);
         }
@@ -202,6 +225,96 @@ export default function MenuBarPopover() {
     };
   }, []);
 
+  // Views opened by trivyn:// links and launch arguments. They are queued in
+  // the backend because they may arrive before this window has loaded
+  useEffect(() => {
+    const openRoute = (route: PopoverRoute) => {
+      if (route.view === 'toggle') {
+        setToggleRequest(route.goal);
+        openRoute(route.shownIn);
+      } else if (route.view === 'goals') {
+        setBottomTab('goals');
+        setSelectedLevel(route.level);
+      } else {
+        setBottomTab(route.view);
+        setPopoverRoute(route);
+      }
+    };
+
+    const takePopoverRoute = async () => {
+      const route = await invoke<PopoverRoute | null>('take_popover_route');
+      if (route) openRoute(route);
+    };
+
+    takePopoverRoute();
+    const unlisten = listen('popover-route-requested', () => {
+      takePopoverRoute();
+    });
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  // Tray menu entries open the popover on a tab
+  useEffect(() => {
+    const unlisten = listen<string>('tray-navigate', (event) => {
+      if (event.payload === 'add-goal') {
+        setSelectedLevel('daily');
+        setBottomTab('goals');
+      } else {
+        setBottomTab(event.payload as BottomTab);
+      }
+    });
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  // Reminders arrive as desktop notifications and never open the popover by
+  // themselves. It opens when the user clicks one (or presses "Reflect now" /
+  // "Plan now"); the tray menu offers the same choices where notifications
+  // have no buttons
+  useEffect(() => {
+    const setupNotificationActions = async () => {
+      try {
+        await registerActionTypes([
+          {
+            id: 'reflection-reminder',
+            actions: [
+              { id: 'reflect_now', title: t('reminder.reflectNow'), foreground: true },
+              { id: 'snooze', title: t('reminder.snooze') },
+              { id: 'skip_week', title: t('reminder.skipWeek') },
+            ],
+          },
+          {
+            id: 'planning-reminder',
+            actions: [
+              { id: 'plan_now', title: t('reminder.planNow'), foreground: true },
+              { id: 'snooze', title: t('reminder.snooze') },
+              { id: 'skip_week', title: t('reminder.skipWeek') },
+            ],
+          },
+        ]);
+      } catch (error) {
+        console.warn('[MenuBarPopover] Notification actions are not supported here:', error);
+      }
+
+      return onAction((payload) => {
+        // A click on the notification itself opens the tab its kind is about
+        const { actionId, actionTypeId } = payload as { actionId?: string; actionTypeId?: string };
+        const action = actionId ?? (actionTypeId === 'planning-reminder' ? 'plan_now' : 'reflect_now');
+        invoke('handle_reminder_action', { action }).catch((error) => {
+          console.error('[MenuBarPopover] Failed to handle reminder action:', error);
+        });
+      });
+    };
+
+    const cleanup = setupNotificationActions();
+    return () => {
+      cleanup.then(listener => listener.unregister());
+    };
+  }, [t]);
+
   // Reset confetti when changing tabs (level or bottom tab)
   useEffect(() => {
     setShowConfetti(false);
@@ -247,7 +360,7 @@ export default function MenuBarPopover() {
 
         // Add header height (approximately 60px for the Trivyn header)
         // and the update banner height when visible
-        const totalHeight = targetHeight + 60 + (updateStatus !== 'idle' ? 36 : 0);
+        const totalHeight = targetHeight + 60 + (updateStatus !== 'idle' ? 36 : 0) + (toggleRequest ? 36 : 0);
 
         console.log(`[MenuBarPopover] Resizing to ${totalHeight}px for tab: ${bottomTab}`);
         await invoke('resize_popover', { height: totalHeight });
@@ -256,7 +369,7 @@ export default function MenuBarPopover() {
       }
     };
     resizeWindow();
-  }, [bottomTab, goalsHeight, reflectionHeight, historyHeight, settingsHeight, updateStatus]);
+  }, [bottomTab, goalsHeight, reflectionHeight, historyHeight, settingsHeight, updateStatus, toggleRequest]);
 
   const currentGoals = selectedLevel === 'daily' ? getDailyGoals()
     : selectedLevel === 'weekly' ? getWeeklyGoals()
@@ -296,6 +409,16 @@ export default function MenuBarPopover() {
     }
   };
 
+  const confirmToggleRequest = async () => {
+    if (!toggleRequest) return;
+    try {
+      await toggleGoalCompletion(toggleRequest.id);
+    } catch (error) {
+      console.error('Failed to toggle goal:', error);
+    }
+    setToggleRequest(null);
+  };
+
   const handlePlanNext = (level: GoalLevel) => {
     setSelectedLevel(level);
     setBottomTab('goals');
@@ -415,6 +538,32 @@ export default function MenuBarPopover() {
           </div>
         )}
 
+        {/* Toggle link prompt - 36px height */}
+        {toggleRequest && (
+          <div
+            className="flex items-center gap-3 px-4 border-b border-border-subtle dark:border-gray-700 bg-surface-elevated/50 dark:bg-surface-dark-elevated/50"
+            style={{ height: '36px' }}
+          >
+            <span className="flex-1 min-w-0 truncate text-xs text-secondary dark:text-content-dark-secondary">
+              {t(toggleRequest.isCompleted ? 'goals.toggleLink.markOpen' : 'goals.toggleLink.markDone', {
+                title: toggleRequest.title,
+              })}
+            </span>
+            <button
+              onClick={confirmToggleRequest}
+              className="flex-shrink-0 text-[11px] font-semibold text-brand-primary hover:underline"
+            >
+              {t('goals.toggleLink.confirm')}
+            </button>
+            <button
+              onClick={() => setToggleRequest(null)}
+              className="flex-shrink-0 text-[11px] font-semibold text-secondary hover:underline"
+            >
+              {t('goals.toggleLink.dismiss')}
+            </button>
+          </div>
+        )}
+
         {/* Content area */}
         <div className="flex-1 overflow-auto">
           {bottomTab === 'goals' && (
@@ -521,10 +670,18 @@ export default function MenuBarPopover() {
               onHeightChange={setReflectionHeight}
               trigger={reflectionTrigger}
               onTriggerConsumed={() => setReflectionTrigger(null)}
+              route={popoverRoute?.view === 'reflection' ? popoverRoute : null}
+              onRouteConsumed={() => setPopoverRoute(null)}
               onPlanNext={handlePlanNext}
             />
           )}
-          {bottomTab === 'history' && <HistoryView onHeightChange={setHistoryHeight} />}
+          {bottomTab === 'history' && (
+            <HistoryView
+              onHeightChange={setHistoryHeight}
+              route={popoverRoute?.view === 'history' ? popoverRoute : null}
+              onRouteConsumed={() => setPopoverRoute(null)}
+            />
+          )}
           {bottomTab === 'settings' && <SettingsView onHeightChange={setSettingsHeight} />}
         </div>
       </div>
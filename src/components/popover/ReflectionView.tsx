@@ -3,7 +3,7 @@ import { useTranslation } from 'react-i18next';
 import { useReflectionStore } from '../../store/reflectionStore';
 import { useGoalStore } from '../../store/goalStore';
 import { useSettingsStore } from '../../store/settingsStore';
-import { GoalLevel } from '../../types';
+import { GoalLevel, PopoverRoute, ReviewReport } from '../../types';
 import {
   addPeriods,
   formatPeriodLabel,
@@ -15,20 +15,26 @@ import {
 export interface PeriodChangeEvent {
   has_weekly_change: boolean;
   has_monthly_change: boolean;
+  has_daily_review: boolean;
   current_week_key: string;
   current_month_key: string;
+  current_day_key: string;
+  weekly_review: ReviewReport | null;
+  monthly_review: ReviewReport | null;
 }
 
 interface ReflectionViewProps {
   onHeightChange?: (height: number) => void;
   trigger?: PeriodChangeEvent | null;
   onTriggerConsumed?: () => void;
+  route?: Extract<PopoverRoute, { view: 'reflection' }> | null;
+  onRouteConsumed?: () => void;
   onPlanNext?: (level: GoalLevel) => void;
 }
 
-type ReflectionLevel = 'weekly' | 'monthly';
+type ReflectionLevel = 'daily' | 'weekly' | 'monthly';
 
-export default function ReflectionView({ onHeightChange, trigger, onTriggerConsumed, onPlanNext }: ReflectionViewProps) {
+export default function ReflectionView({ onHeightChange, trigger, onTriggerConsumed, route, onRouteConsumed, onPlanNext }: ReflectionViewProps) {
   const { t, i18n } = useTranslation();
   const [level, setLevel] = useState<ReflectionLevel>('weekly');
   const [targetDate, setTargetDate] = useState<Date>(() => new Date());
@@ -96,7 +102,7 @@ export default function ReflectionView({ onHeightChange, trigger, onTriggerConsu
     if (!trigger || lastAppliedTriggerRef.current === trigger) return;
     lastAppliedTriggerRef.current = trigger;
 
-    const { has_weekly_change, has_monthly_change } = trigger;
+    const { has_weekly_change, has_monthly_change, has_daily_review } = trigger;
 
     if (has_weekly_change) {
       setLevel('weekly');
@@ -106,11 +112,25 @@ export default function ReflectionView({ onHeightChange, trigger, onTriggerConsu
       setLevel('monthly');
       setTargetDate(addPeriods('monthly', new Date(), -1));
       setShowMonthlyNotice(false);
+    } else if (has_daily_review) {
+      // End-of-day shutdown review targets today
+      setLevel('daily');
+      setTargetDate(new Date());
+      setShowMonthlyNotice(false);
     }
 
     onTriggerConsumed?.();
   }, [trigger, onTriggerConsumed]);
 
+  // Open the period a trivyn://reflect link or --reflect points at
+  useEffect(() => {
+    if (!route) return;
+    setLevel(route.level);
+    setTargetDate(new Date(route.periodStart));
+    setShowMonthlyNotice(false);
+    onRouteConsumed?.();
+  }, [route, onRouteConsumed]);
+
   const handleSave = async () => {
     if (isSaving) return;
     setIsSaving(true);
@@ -183,10 +203,10 @@ export default function ReflectionView({ onHeightChange, trigger, onTriggerConsu
 
   return (
     <div ref={contentRef} className="flex flex-col">
-      {/* Level tabs - only weekly and monthly for reflection */}
+      {/* Level tabs */}
       <div className="px-4 py-3">
         <div className="flex gap-1 p-1 bg-surface-elevated/50 dark:bg-surface-dark-elevated/50 rounded-lg">
-          {(['weekly', 'monthly'] as ReflectionLevel[]).map((lvl) => (
+          {(['daily', 'weekly', 'monthly'] as ReflectionLevel[]).map((lvl) => (
             <button
               key={lvl}
               onClick={() => handleLevelChange(lvl)}
@@ -1,7 +1,8 @@
-import { useEffect, useRef, useState } from 'react';
+import { useEffect, useRef, useState, type KeyboardEvent } from 'react';
 import { useTranslation } from 'react-i18next';
 import { getVersion } from '@tauri-apps/api/app';
 import { useSettingsStore } from '../../store/settingsStore';
+import { HotkeyAction } from '../../types';
 
 interface SettingsViewProps {
   onHeightChange?: (height: number) => void;
@@ -34,17 +35,82 @@ const DayButton = ({ label, active, onClick }: { label: string; active: boolean;
   </button>
 );
 
+const isMac = navigator.userAgent.includes('Mac');
+
+// "CommandOrControl+Alt+KeyF" -> "⌘⌥F" on macOS, "Ctrl+Alt+F" elsewhere
+const formatShortcut = (shortcut: string) =>
+  shortcut
+    .split('+')
+    .map((part) => {
+      switch (part.toLowerCase()) {
+        case 'commandorcontrol':
+        case 'cmdorctrl':
+          return isMac ? '⌘' : 'Ctrl';
+        case 'super':
+        case 'command':
+        case 'cmd':
+          return isMac ? '⌘' : 'Super';
+        case 'control':
+        case 'ctrl':
+          return isMac ? '⌃' : 'Ctrl';
+        case 'alt':
+        case 'option':
+          return isMac ? '⌥' : 'Alt';
+        case 'shift':
+          return isMac ? '⇧' : 'Shift';
+        default:
+          return part.replace(/^(Key|Digit)/, '');
+      }
+    })
+    .join(isMac ? '' : '+');
+
+// Accelerator of a key press, or null while only modifiers are held
+const shortcutFromEvent = (e: KeyboardEvent) => {
+  if (['Control', 'Alt', 'Shift', 'Meta'].includes(e.key)) return null;
+  const parts = [];
+  if (e.ctrlKey) parts.push('Control');
+  if (e.altKey) parts.push('Alt');
+  if (e.shiftKey) parts.push('Shift');
+  if (e.metaKey) parts.push('Super');
+  parts.push(e.code);
+  return parts.join('+');
+};
+
 export default function SettingsView({ onHeightChange }: SettingsViewProps) {
   const { t, i18n } = useTranslation();
-  const { weekStart, language, theme, reflectionPromptEnabled, loadSettings, setWeekStart, setLanguage, setTheme, setReflectionPromptEnabled } = useSettingsStore();
+  const { weekStart, language, theme, reflectionPromptEnabled, dailyReflectionTime, planningReminderTime, planningMinGoals, trayShowWeeklyGoals, hotkeys, quietHours, reminderStatus, loadSettings, loadReminderSettings, loadHotkeys, setWeekStart, setLanguage, setTheme, setReflectionPromptEnabled, setDailyReflectionTime, setPlanningReminderTime, setPlanningMinGoals, setTrayShowWeeklyGoals, setHotkey, setQuietHours, pauseReminders, resumeReminders } = useSettingsStore();
   const contentRef = useRef<HTMLDivElement>(null);
   // リリースビルドでは CI がタグから書き込んだ tauri.conf.json のバージョンが返る
   const [appVersion, setAppVersion] = useState<string | null>(null);
+  const [recordingHotkey, setRecordingHotkey] = useState<HotkeyAction | null>(null);
+  const [hotkeyError, setHotkeyError] = useState<{ action: HotkeyAction; message: string } | null>(null);
 
   useEffect(() => {
     loadSettings();
+    loadReminderSettings();
+    loadHotkeys();
     getVersion().then(setAppVersion).catch(() => setAppVersion(null));
-  }, [loadSettings]);
+  }, [loadSettings, loadReminderSettings, loadHotkeys]);
+
+  const handleHotkeyKeyDown = async (action: HotkeyAction, e: KeyboardEvent) => {
+    e.preventDefault();
+    e.stopPropagation();
+    if (e.key === 'Escape') {
+      setRecordingHotkey(null);
+      return;
+    }
+    // Backspace / Delete でショートカットを無効にする
+    const shortcut = e.key === 'Backspace' || e.key === 'Delete' ? '' : shortcutFromEvent(e);
+    if (shortcut === null) return;
+
+    setRecordingHotkey(null);
+    try {
+      await setHotkey(action, shortcut);
+      setHotkeyError(null);
+    } catch (error) {
+      setHotkeyError({ action, message: String(error) });
+    }
+  };
 
   // Notify parent of height changes
   useEffect(() => {
@@ -73,6 +139,15 @@ export default function SettingsView({ onHeightChange }: SettingsViewProps) {
     ? ['日', '月', '火', '水', '木', '金', '土']
     : ['S', 'M', 'T', 'W', 'T', 'F', 'S'];
 
+  // Quiet hours: at most one window per weekday in this view
+  const updateQuietWindow = (weekday: number, range: { start: string; end: string } | null) => {
+    const others = quietHours.filter((w) => w.weekday !== weekday);
+    setQuietHours(range ? [...others, { weekday, ...range }].sort((a, b) => a.weekday - b.weekday) : others);
+  };
+
+  const formatTime = (timestamp: number) =>
+    new Date(timestamp).toLocaleTimeString(i18n.language, { hour: '2-digit', minute: '2-digit' });
+
   const languageOptions = [
     { value: 'system', label: t('settings.language.system') },
     { value: 'ja', label: '日本語' },
@@ -159,6 +234,141 @@ export default function SettingsView({ onHeightChange }: SettingsViewProps) {
           <span className="text-sm text-primary">{t('settings.notifications.reflectionPrompt')}</span>
           <Toggle checked={reflectionPromptEnabled} onChange={setReflectionPromptEnabled} />
         </div>
+        <div className="flex items-center justify-between p-3 bg-surface-elevated/50 dark:bg-surface-dark-elevated/50 rounded-md">
+          <span className="text-sm text-primary">{t('settings.notifications.dailyReflection')}</span>
+          <input
+            type="time"
+            value={dailyReflectionTime}
+            onChange={(e) => setDailyReflectionTime(e.target.value)}
+            className="text-sm bg-transparent text-primary"
+          />
+        </div>
+        <div className="flex items-center justify-between p-3 bg-surface-elevated/50 dark:bg-surface-dark-elevated/50 rounded-md">
+          <span className="text-sm text-primary">{t('settings.notifications.planningReminder')}</span>
+          <input
+            type="time"
+            value={planningReminderTime}
+            onChange={(e) => setPlanningReminderTime(e.target.value)}
+            className="text-sm bg-transparent text-primary"
+          />
+        </div>
+        <div className="flex items-center justify-between p-3 bg-surface-elevated/50 dark:bg-surface-dark-elevated/50 rounded-md">
+          <span className="text-sm text-primary">{t('settings.notifications.planningMinGoals')}</span>
+          <select
+            value={planningMinGoals}
+            onChange={(e) => setPlanningMinGoals(Number(e.target.value))}
+            className="text-sm bg-transparent text-primary"
+          >
+            {[1, 2, 3].map((count) => (
+              <option key={count} value={count}>{count}</option>
+            ))}
+          </select>
+        </div>
+        <div className="flex items-center justify-between p-3 bg-surface-elevated/50 dark:bg-surface-dark-elevated/50 rounded-md">
+          <span className="text-sm text-primary">
+            {reminderStatus?.pausedUntil
+              ? t('settings.notifications.pausedUntil', { time: formatTime(reminderStatus.pausedUntil) })
+              : t('settings.notifications.pause')}
+          </span>
+          {reminderStatus?.pausedUntil ? (
+            <button onClick={resumeReminders} className="text-sm text-primary underline">
+              {t('settings.notifications.resume')}
+            </button>
+          ) : (
+            <select
+              value=""
+              onChange={(e) => e.target.value && pauseReminders(Number(e.target.value))}
+              className="text-sm bg-transparent text-primary"
+            >
+              <option value="">-</option>
+              {[1, 2, 4, 8].map((hours) => (
+                <option key={hours} value={hours}>{t('settings.notifications.hours', { count: hours })}</option>
+              ))}
+            </select>
+          )}
+        </div>
+      </div>
+
+      {/* Quiet hours setting */}
+      <div className="space-y-3">
+        <label className="text-sm font-bold text-primary block">
+          {t('settings.quietHours.label')}
+        </label>
+        <p className="text-xs text-tertiary">{t('settings.quietHours.description')}</p>
+        {dayLabels.map((label, weekday) => {
+          const quietWindow = quietHours.find((w) => w.weekday === weekday);
+          return (
+            <div key={weekday} className="flex items-center gap-2 p-2 bg-surface-elevated/50 dark:bg-surface-dark-elevated/50 rounded-md">
+              <span className="w-6 text-sm font-bold text-primary">{label}</span>
+              <Toggle
+                checked={!!quietWindow}
+                onChange={(enabled) => updateQuietWindow(weekday, enabled ? { start: '22:00', end: '07:00' } : null)}
+              />
+              {quietWindow && (
+                <>
+                  <input
+                    type="time"
+                    value={quietWindow.start}
+                    onChange={(e) => updateQuietWindow(weekday, { start: e.target.value, end: quietWindow.end })}
+                    className="text-sm bg-transparent text-primary"
+                  />
+                  <span className="text-tertiary">–</span>
+                  <input
+                    type="time"
+                    value={quietWindow.end}
+                    onChange={(e) => updateQuietWindow(weekday, { start: quietWindow.start, end: e.target.value })}
+                    className="text-sm bg-transparent text-primary"
+                  />
+                </>
+              )}
+            </div>
+          );
+        })}
+      </div>
+
+      {/* Tray menu setting */}
+      <div className="space-y-3">
+        <label className="text-sm font-bold text-primary block">
+          {t('settings.tray.label')}
+        </label>
+        <div className="flex items-center justify-between p-3 bg-surface-elevated/50 dark:bg-surface-dark-elevated/50 rounded-md">
+          <span className="text-sm text-primary">{t('settings.tray.showWeeklyGoals')}</span>
+          <Toggle checked={trayShowWeeklyGoals} onChange={setTrayShowWeeklyGoals} />
+        </div>
+      </div>
+
+      {/* Global shortcuts */}
+      <div className="space-y-3">
+        <label className="text-sm font-bold text-primary block">
+          {t('settings.hotkeys.label')}
+        </label>
+        <p className="text-xs text-tertiary">{t('settings.hotkeys.description')}</p>
+        {hotkeys.map((binding) => {
+          const error = hotkeyError?.action === binding.action ? hotkeyError.message : binding.error;
+          return (
+            <div key={binding.action} className="p-3 bg-surface-elevated/50 dark:bg-surface-dark-elevated/50 rounded-md space-y-1">
+              <div className="flex items-center justify-between">
+                <span className="text-sm text-primary">{t(`settings.hotkeys.${binding.action}`)}</span>
+                <button
+                  onClick={() => setRecordingHotkey(binding.action)}
+                  onKeyDown={(e) => recordingHotkey === binding.action && handleHotkeyKeyDown(binding.action, e)}
+                  onBlur={() => setRecordingHotkey(null)}
+                  style={recordingHotkey === binding.action ? { background: 'linear-gradient(135deg, #3B82F6 0%, #60A5FA 100%)' } : {}}
+                  className={`px-2 py-1 rounded-md text-xs font-bold ${recordingHotkey === binding.action ? 'text-white' : 'text-secondary bg-surface-elevated dark:bg-surface-dark-elevated'}`}
+                >
+                  {recordingHotkey === binding.action
+                    ? t('settings.hotkeys.recording')
+                    : binding.shortcut
+                      ? formatShortcut(binding.shortcut)
+                      : t('settings.hotkeys.off')}
+                </button>
+              </div>
+              {error && (
+                <p className="text-xs text-red-400">{t('settings.hotkeys.failed', { error })}</p>
+              )}
+            </div>
+          );
+        })}
       </div>
 
       {/* Footer */}
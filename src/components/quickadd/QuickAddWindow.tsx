@@ -0,0 +1,165 @@
+import { useEffect, useRef, useState } from 'react';
+import { useTranslation } from 'react-i18next';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { useSettingsStore } from '../../store/settingsStore';
+import { GoalLevel, LinkedGoal, QuickAddPreview } from '../../types';
+
+// ライトモードでは暗めのアクセント（コントラスト確保）、ダークモードでは通常のアクセント
+const LEVEL_BADGE: Record<GoalLevel, string> = {
+  daily: 'bg-daily-accent/15 text-daily-accent-text dark:text-daily-accent',
+  weekly: 'bg-weekly-accent/15 text-weekly-accent-text dark:text-weekly-accent',
+  monthly: 'bg-monthly-accent/15 text-monthly-accent-text dark:text-monthly-accent',
+};
+
+const hideWindow = async () => {
+  const { Window } = await import('@tauri-apps/api/window');
+  await Window.getCurrent().hide();
+};
+
+export default function QuickAddWindow() {
+  const { t } = useTranslation();
+  const { loadSettings } = useSettingsStore();
+  const [input, setInput] = useState('');
+  // A link's goal is previewed and added as given, without reading its title
+  // as a quick-add line (so "Fix #123" keeps its "#123")
+  const [linkedLevel, setLinkedLevel] = useState<GoalLevel | null>(null);
+  const [preview, setPreview] = useState<QuickAddPreview | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const inputRef = useRef<HTMLInputElement>(null);
+  // 入力が速いと古いプレビューが後から届くので、最新の要求だけを反映する
+  const requestRef = useRef(0);
+
+  // The backend shows the window from the hotkey, the tray or a trivyn://add
+  // link; start empty (or with the link's goal) and pick up language or
+  // theme changes made since the last time
+  useEffect(() => {
+    const takeInput = () => {
+      invoke<LinkedGoal | null>('take_quick_add_goal')
+        .then((pending) => {
+          if (!pending) return;
+          setLinkedLevel(pending.level);
+          setInput(pending.title);
+        })
+        .catch((e) => console.error('Failed to take quick-add goal:', e));
+    };
+
+    loadSettings();
+    takeInput();
+    const unlisten = listen('quick-add-opened', () => {
+      setInput('');
+      setLinkedLevel(null);
+      setPreview(null);
+      setError(null);
+      loadSettings();
+      takeInput();
+      inputRef.current?.focus();
+    });
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, [loadSettings]);
+
+  useEffect(() => {
+    const request = ++requestRef.current;
+    if (!input.trim()) {
+      setPreview(null);
+      setError(null);
+      return;
+    }
+    const previewed = linkedLevel
+      ? invoke<QuickAddPreview>('preview_quick_add_goal', { title: input, level: linkedLevel })
+      : invoke<QuickAddPreview>('preview_quick_add', { input });
+    previewed
+      .then((result) => {
+        if (request !== requestRef.current) return;
+        setPreview(result);
+        setError(null);
+      })
+      .catch((e) => {
+        if (request !== requestRef.current) return;
+        setPreview(null);
+        setError(String(e));
+      });
+  }, [input, linkedLevel]);
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!input.trim() || preview?.error) return;
+    try {
+      if (linkedLevel) {
+        await invoke('commit_quick_add_goal', { title: input, level: linkedLevel });
+      } else {
+        await invoke('commit_quick_add', { input });
+      }
+      setInput('');
+      setLinkedLevel(null);
+      await hideWindow();
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const handleKeyDown = (e: React.KeyboardEvent) => {
+    if (e.key === 'Escape') {
+      e.preventDefault();
+      hideWindow();
+    }
+  };
+
+  return (
+    <div className="w-full h-full p-2">
+      <form onSubmit={handleSubmit} className="glass-card rounded-xl overflow-hidden">
+        <div className="flex items-center gap-3 px-4 py-3" data-tauri-drag-region>
+          <svg className="w-5 h-5 text-tertiary flex-shrink-0" fill="none" stroke="currentColor" strokeWidth={2} viewBox="0 0 24 24">
+            <path strokeLinecap="round" strokeLinejoin="round" d="M12 4v16m8-8H4" />
+          </svg>
+          <input
+            ref={inputRef}
+            type="text"
+            autoFocus
+            value={input}
+            onChange={(e) => {
+              setInput(e.target.value);
+              // 空にしたら通常の入力に戻る
+              if (!e.target.value) setLinkedLevel(null);
+            }}
+            onKeyDown={handleKeyDown}
+            placeholder={t('quickAdd.placeholder')}
+            className="flex-1 bg-transparent text-lg text-primary placeholder-tertiary dark:placeholder-content-dark-tertiary outline-none"
+          />
+        </div>
+
+        <div className="px-4 py-2 border-t border-border-subtle dark:border-gray-700 text-xs">
+          {preview ? (
+            <div className="space-y-1">
+              <div className="flex items-center gap-2 flex-wrap">
+                <span className={`px-2 py-0.5 rounded-md font-bold ${LEVEL_BADGE[preview.level]}`}>
+                  {t(`levels.${preview.level}`)}
+                </span>
+                <span className="text-secondary">{preview.periodKey}</span>
+                <span className="text-primary font-semibold truncate">{preview.title}</span>
+              </div>
+              <div className="flex items-center gap-2 flex-wrap text-tertiary">
+                {preview.parent && (
+                  <span>{t('quickAdd.parent', { title: preview.parent.title })}</span>
+                )}
+                {preview.tags.map((tag) => (
+                  <span key={tag}>#{tag}</span>
+                ))}
+                {preview.unmatchedHints.map((hint) => (
+                  <span key={hint}>{t('quickAdd.noMatch', { hint })}</span>
+                ))}
+              </div>
+              {preview.error && <p className="text-red-400">{preview.error}</p>}
+            </div>
+          ) : error ? (
+            <p className="text-red-400">{error}</p>
+          ) : (
+            <p className="text-tertiary">{t('quickAdd.hint')}</p>
+          )}
+        </div>
+      </form>
+    </div>
+  );
+}
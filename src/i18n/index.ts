@@ -1,17 +1,21 @@
 import i18n from 'i18next';
 import { initReactI18next } from 'react-i18next';
 import LanguageDetector from 'i18next-browser-languagedetector';
-import ja from './locales/ja.json';
-import en from './locales/en.json';
+
+// Every file in ./locales is a language; the Rust backend embeds the same files
+const locales = import.meta.glob<Record<string, unknown>>('./locales/*.json', { eager: true, import: 'default' });
+const resources = Object.fromEntries(
+  Object.entries(locales).map(([path, translation]) => [
+    path.replace(/^.*\/(.+)\.json$/, '$1'),
+    { translation },
+  ])
+);
 
 i18n
   .use(LanguageDetector)
   .use(initReactI18next)
   .init({
-    resources: {
-      ja: { translation: ja },
-      en: { translation: en },
-    },
+    resources,
     fallbackLng: 'ja',
     debug: false,
     interpolation: {
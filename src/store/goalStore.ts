@@ -51,6 +51,8 @@ interface GoalStore {
   toggleGoalCompletion: (goalId: string) => Promise<Goal>;
   updateGoal: (goalId: string, title: string) => Promise<void>;
   deleteGoal: (goalId: string) => Promise<void>;
+  undo: () => Promise<void>;
+  redo: () => Promise<void>;
   setSelectedLevel: (level: GoalLevel) => void;
   setWeekStart: (weekStart: number) => void;
   setupEventListeners: () => Promise<UnlistenFn>;
@@ -162,6 +164,24 @@ export const useGoalStore = create<GoalStore>((set, get) => ({
     }
   },
 
+  undo: async () => {
+    try {
+      await invoke('undo');
+      // goals-updated is emitted from Rust backend
+    } catch (error) {
+      console.log('[goalStore] Undo not applied:', error);
+    }
+  },
+
+  redo: async () => {
+    try {
+      await invoke('redo');
+      // goals-updated is emitted from Rust backend
+    } catch (error) {
+      console.log('[goalStore] Redo not applied:', error);
+    }
+  },
+
   setSelectedLevel: (level: GoalLevel) => {
     set({ selectedLevel: level });
   },
@@ -1,6 +1,6 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
-import { Reflection, GoalLevel } from '../types';
+import { Reflection, ReflectionDetails, GoalLevel } from '../types';
 
 interface ReflectionStore {
   reflections: Map<string, Reflection>;
@@ -14,7 +14,8 @@ interface ReflectionStore {
     periodKey: string,
     insight1: string,
     insight2: string,
-    insight3: string
+    insight3: string,
+    details?: ReflectionDetails
   ) => Promise<void>;
   getReflection: (level: GoalLevel, periodKey: string) => Reflection | null;
 }
@@ -52,7 +53,8 @@ export const useReflectionStore = create<ReflectionStore>((set, get) => ({
     periodKey: string,
     insight1: string,
     insight2: string,
-    insight3: string
+    insight3: string,
+    details?: ReflectionDetails
   ) => {
     try {
       const savedReflection = await invoke<Reflection>('save_reflection', {
@@ -61,6 +63,7 @@ export const useReflectionStore = create<ReflectionStore>((set, get) => ({
         insight1: insight1 || null,
         insight2: insight2 || null,
         insight3: insight3 || null,
+        details: details ?? null,
       });
 
       const key = `${level}-${periodKey}`;
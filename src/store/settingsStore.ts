@@ -1,7 +1,7 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
-import { AppLanguage, AppTheme, WindowPosition } from '../types';
+import { AppLanguage, AppTheme, HotkeyAction, HotkeyBinding, QuietWindow, ReminderStatus, WindowPosition } from '../types';
 import i18n from '../i18n';
 
 interface SettingsStore {
@@ -10,6 +10,13 @@ interface SettingsStore {
   theme: AppTheme;
   floatingWindowPosition: WindowPosition;
   reflectionPromptEnabled: boolean;
+  dailyReflectionTime: string;
+  planningReminderTime: string;
+  planningMinGoals: number;
+  trayShowWeeklyGoals: boolean;
+  hotkeys: HotkeyBinding[];
+  quietHours: QuietWindow[];
+  reminderStatus: ReminderStatus | null;
   autostartEnabled: boolean;
   loading: boolean;
 
@@ -20,6 +27,18 @@ interface SettingsStore {
   setTheme: (theme: AppTheme) => Promise<void>;
   setFloatingWindowPosition: (pos: WindowPosition) => Promise<void>;
   setReflectionPromptEnabled: (enabled: boolean) => Promise<void>;
+  setDailyReflectionTime: (time: string) => Promise<void>;
+  setPlanningReminderTime: (time: string) => Promise<void>;
+  setPlanningMinGoals: (count: number) => Promise<void>;
+  setTrayShowWeeklyGoals: (enabled: boolean) => Promise<void>;
+  loadHotkeys: () => Promise<void>;
+  setHotkey: (action: HotkeyAction, shortcut: string) => Promise<void>;
+  loadReminderSettings: () => Promise<void>;
+  setQuietHours: (windows: QuietWindow[]) => Promise<void>;
+  snoozeReminders: (minutes: number) => Promise<void>;
+  skipRemindersForWeek: () => Promise<void>;
+  pauseReminders: (hours: number) => Promise<void>;
+  resumeReminders: () => Promise<void>;
   setAutostartEnabled: (enabled: boolean) => Promise<void>;
 }
 
@@ -44,6 +63,13 @@ export const useSettingsStore = create<SettingsStore>((set) => ({
   theme: 'system',
   floatingWindowPosition: { x: 0, y: 0 },
   reflectionPromptEnabled: true,
+  dailyReflectionTime: '21:00',
+  planningReminderTime: '09:00',
+  planningMinGoals: 3,
+  trayShowWeeklyGoals: false,
+  hotkeys: [],
+  quietHours: [],
+  reminderStatus: null,
   autostartEnabled: false,
   loading: false,
 
@@ -55,10 +81,10 @@ export const useSettingsStore = create<SettingsStore>((set) => ({
       const theme = (settings.theme as AppTheme) || 'system';
 
       // Apply language to i18n
-      let i18nLang = lang;
+      let i18nLang: string = lang;
       if (lang === 'system') {
-        // Use browser's language detection
-        i18nLang = navigator.language.startsWith('ja') ? 'ja' : 'en';
+        // Same detection as the backend (tray, notifications, reports)
+        i18nLang = await invoke<string>('get_system_language');
       }
       await i18n.changeLanguage(i18nLang);
 
@@ -90,6 +116,10 @@ export const useSettingsStore = create<SettingsStore>((set) => ({
           settings.floating_window_position || '{"x":0,"y":0}'
         ),
         reflectionPromptEnabled: settings.reflection_prompt_enabled !== 'false',
+        dailyReflectionTime: settings.daily_reflection_time ?? '21:00',
+        planningReminderTime: settings.planning_reminder_time ?? '09:00',
+        planningMinGoals: parseInt(settings.planning_min_goals ?? '3', 10) || 3,
+        trayShowWeeklyGoals: settings.tray_show_weekly_goals === 'true',
         autostartEnabled,
         loading: false,
       });
@@ -111,9 +141,9 @@ export const useSettingsStore = create<SettingsStore>((set) => ({
   setLanguage: async (lang: AppLanguage) => {
     try {
       // Apply language to i18n
-      let i18nLang = lang;
+      let i18nLang: string = lang;
       if (lang === 'system') {
-        i18nLang = navigator.language.startsWith('ja') ? 'ja' : 'en';
+        i18nLang = await invoke<string>('get_system_language');
       }
       await i18n.changeLanguage(i18nLang);
 
@@ -174,6 +204,128 @@ export const useSettingsStore = create<SettingsStore>((set) => ({
     }
   },
 
+  setDailyReflectionTime: async (time: string) => {
+    try {
+      await invoke('set_setting', {
+        key: 'daily_reflection_time',
+        value: time,
+      });
+      set({ dailyReflectionTime: time });
+    } catch (error) {
+      console.error('Failed to set daily reflection time:', error);
+    }
+  },
+
+  setPlanningReminderTime: async (time: string) => {
+    try {
+      await invoke('set_setting', {
+        key: 'planning_reminder_time',
+        value: time,
+      });
+      set({ planningReminderTime: time });
+    } catch (error) {
+      console.error('Failed to set planning reminder time:', error);
+    }
+  },
+
+  setPlanningMinGoals: async (count: number) => {
+    try {
+      await invoke('set_setting', {
+        key: 'planning_min_goals',
+        value: String(count),
+      });
+      set({ planningMinGoals: count });
+    } catch (error) {
+      console.error('Failed to set planning minimum goals:', error);
+    }
+  },
+
+  setTrayShowWeeklyGoals: async (enabled: boolean) => {
+    try {
+      // Saved via Rust so the tray menu is rebuilt right away
+      await invoke('set_tray_show_weekly_goals', { enabled });
+      set({ trayShowWeeklyGoals: enabled });
+    } catch (error) {
+      console.error('Failed to set tray weekly goals:', error);
+    }
+  },
+
+  loadHotkeys: async () => {
+    try {
+      const hotkeys = await invoke<HotkeyBinding[]>('get_hotkeys');
+      set({ hotkeys });
+    } catch (error) {
+      console.error('Failed to load hotkeys:', error);
+    }
+  },
+
+  // Rejects with the backend's reason (invalid, already used, taken by
+  // another application); the previous shortcut stays active then
+  setHotkey: async (action: HotkeyAction, shortcut: string) => {
+    const binding = await invoke<HotkeyBinding>('set_hotkey', { action, shortcut });
+    set((state) => ({
+      hotkeys: state.hotkeys.map((h) => (h.action === action ? binding : h)),
+    }));
+  },
+
+  loadReminderSettings: async () => {
+    try {
+      const [quietHours, reminderStatus] = await Promise.all([
+        invoke<QuietWindow[]>('get_quiet_hours'),
+        invoke<ReminderStatus>('get_reminder_status'),
+      ]);
+      set({ quietHours, reminderStatus });
+    } catch (error) {
+      console.error('Failed to load reminder settings:', error);
+    }
+  },
+
+  setQuietHours: async (windows: QuietWindow[]) => {
+    try {
+      const quietHours = await invoke<QuietWindow[]>('set_quiet_hours', { windows });
+      const reminderStatus = await invoke<ReminderStatus>('get_reminder_status');
+      set({ quietHours, reminderStatus });
+    } catch (error) {
+      console.error('Failed to set quiet hours:', error);
+    }
+  },
+
+  snoozeReminders: async (minutes: number) => {
+    try {
+      const reminderStatus = await invoke<ReminderStatus>('snooze_reminders', { minutes });
+      set({ reminderStatus });
+    } catch (error) {
+      console.error('Failed to snooze reminders:', error);
+    }
+  },
+
+  skipRemindersForWeek: async () => {
+    try {
+      const reminderStatus = await invoke<ReminderStatus>('skip_reminders_for_week');
+      set({ reminderStatus });
+    } catch (error) {
+      console.error('Failed to skip reminders:', error);
+    }
+  },
+
+  pauseReminders: async (hours: number) => {
+    try {
+      const reminderStatus = await invoke<ReminderStatus>('pause_reminders', { hours });
+      set({ reminderStatus });
+    } catch (error) {
+      console.error('Failed to pause reminders:', error);
+    }
+  },
+
+  resumeReminders: async () => {
+    try {
+      const reminderStatus = await invoke<ReminderStatus>('resume_reminders');
+      set({ reminderStatus });
+    } catch (error) {
+      console.error('Failed to resume reminders:', error);
+    }
+  },
+
   setAutostartEnabled: async (enabled: boolean) => {
     try {
       if (enabled) {
@@ -10,6 +10,7 @@ export interface Goal {
   periodStart: number;
   parentGoalId: string | null;
   note: string | null;
+  deletedAt: number | null;
 }
 
 export type AppLanguage = 'system' | 'en' | 'ja';
@@ -34,5 +35,117 @@ export interface Reflection {
   insight1: string | null;
   insight2: string | null;
   insight3: string | null;
+  rating: number | null;
+  mood: number | null;
+  energy: number | null;
+  body: string | null;
+  answers: Record<string, string>;
   createdAt: number;
+  updatedAt: number;
+}
+
+export type ReflectionDetails = Pick<Reflection, 'rating' | 'mood' | 'energy' | 'body' | 'answers'>;
+
+export interface ReflectionPrompt {
+  id: string;
+  level: GoalLevel;
+  text: string;
+  position: number;
+  archivedAt: number | null;
+}
+
+export interface ReviewGoal {
+  goal: Goal;
+  children: Goal[];
+  childrenCompleted: number;
+}
+
+export interface ReviewReport {
+  level: GoalLevel;
+  periodKey: string;
+  periodStart: number;
+  periodEnd: number;
+  generatedAt: number;
+  goals: ReviewGoal[];
+  completed: number;
+  carryOvers: Goal[];
+  streak: { level: GoalLevel; current: number; longest: number };
+  previousReflection: Reflection | null;
+}
+
+/** Window in which reminders are held back; runs past midnight when end <= start */
+export interface QuietWindow {
+  /** 0 = Sunday, ..., 6 = Saturday */
+  weekday: number;
+  start: string;
+  end: string;
+}
+
+export interface ReminderStatus {
+  snoozedUntil: number | null;
+  pausedUntil: number | null;
+  quietUntil: number | null;
+  heldUntil: number | null;
+  skippedWeek: string | null;
+  pending: number;
+}
+
+export type HotkeyAction = 'toggle_floating' | 'open_popover' | 'quick_add' | 'start_reflection';
+
+/** Global shortcut; shortcut is an accelerator such as "CommandOrControl+Alt+F" */
+export interface HotkeyBinding {
+  action: HotkeyAction;
+  /** Empty when the shortcut is turned off */
+  shortcut: string;
+  /** Why the shortcut is inactive, e.g. another application uses it */
+  error: string | null;
+}
+
+/** Goal a trivyn://add link fills into the quick-add window */
+export interface LinkedGoal {
+  title: string;
+  level: GoalLevel;
+}
+
+/** What a quick-add line would add, from the backend parser */
+export interface QuickAddPreview {
+  title: string;
+  level: GoalLevel;
+  periodStart: number;
+  periodKey: string;
+  parent: Goal | null;
+  tags: string[];
+  /** ^hints that matched no goal */
+  unmatchedHints: string[];
+  /** Why the goal cannot be added, e.g. the period is full */
+  error: string | null;
+}
+
+/** Payload of the backend `period-changed` event */
+export interface PeriodChangedEvent {
+  /** Every level; oldKey === newKey for levels that did not change */
+  periods: { level: GoalLevel; oldKey: string; newKey: string }[];
+  /** Noticed right after sleep or a wall-clock jump */
+  clockChanged: boolean;
+}
+
+/** View the popover is asked to open by a trivyn:// link or launch argument */
+export type PopoverRoute =
+  | { view: 'goals'; level: GoalLevel }
+  /** periodStart is the timestamp of the first day of the period */
+  | { view: 'reflection'; level: GoalLevel; periodStart: number }
+  /** date is the timestamp of the selected day */
+  | { view: 'history'; date: number }
+  /** shownIn, with a prompt asking whether to toggle the goal */
+  | { view: 'toggle'; goal: Goal; shownIn: PopoverRoute };
+
+/** Payload of the backend `period-rolled-over` event */
+export interface PeriodRolledOverEvent {
+  /** Levels whose period changed */
+  rolledOver: GoalLevel[];
+  /** The current period of every level; start and end are timestamps */
+  periods: { level: GoalLevel; key: string; start: number; end: number }[];
+  /** When the next day, week or month starts */
+  nextRolloverAt: number;
+  clockChanged: boolean;
 }